@@ -1,30 +1,72 @@
 use axum::extract::*;
 use axum::routing::*;
 use axum::*;
+use chrono::{DateTime, TimeZone, Utc};
 use log;
 use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
 use serde;
 
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use crate::args::Args;
+use crate::decoder::Registry;
+use crate::indexer;
+use crate::model;
 use crate::tables;
 use crate::tables::block::BlockRow;
+use crate::tables::contract_execution::ContractExecutionRow;
+use crate::tables::event::{EventAttributeRow, EventRow};
+use crate::tables::ledger_entry::LedgerEntryRow;
+use crate::tables::proposal::ProposalRow;
 use crate::tables::schema;
 use crate::tables::tx::TxRow;
+use crate::tables::tx_fee::TxFeeRow;
+use crate::tables::tx_signer::TxSignerRow;
+use crate::tables::validator::ValidatorRow;
+use crate::tables::validator_history::ValidatorHistoryRow;
 
-pub async fn init(args: &Args) {
+pub fn router(args: &Args, registry: Arc<Registry>) -> Router {
     let db_path = args.datadir.join(schema::DB_NAME);
 
     let manager = SqliteConnectionManager::file(&db_path);
     let pool = Arc::new(r2d2::Pool::new(manager).unwrap());
 
-    let app = Router::new()
+    Router::new()
         .route("/block/:height", get(query_block_by_height))
         .route("/block/latest", get(query_block_latest))
+        .route("/block/:height/events", get(query_block_events))
         .route("/tx/:hash", get(query_tx_by_hash))
-        .layer(Extension(pool));
+        .route("/tx/:hash/events", get(query_tx_events))
+        .route("/tx/:hash/msgs", get(query_tx_msgs))
+        .route("/proposals", get(query_proposals))
+        .route("/proposal/:id/tally", get(query_proposal_tally))
+        .route(
+            "/contract/:address/executions",
+            get(query_contract_executions),
+        )
+        .route("/validator/:address", get(query_validator))
+        .route("/validator/:address/uptime", get(query_validator_uptime))
+        .route("/validator/:address/missed", get(query_validator_missed))
+        .route(
+            "/validator/:address/delegations",
+            get(query_validator_delegations),
+        )
+        .route(
+            "/address/:address/delegations",
+            get(query_address_delegations),
+        )
+        .route("/address/:address/rewards", get(query_address_rewards))
+        .route("/address/:address/ledger", get(query_address_ledger))
+        .route("/address/:address/balances", get(query_address_balances))
+        .route("/holders", get(query_holders))
+        .layer(Extension(pool))
+        .layer(Extension(registry))
+}
 
+pub async fn init(args: &Args, registry: Arc<Registry>) {
+    let app = router(args, registry);
     let server = Server::bind(&"0.0.0.0:3000".parse().unwrap()).serve(app.into_make_service());
 
     log::info!("REST API started on port 3000");
@@ -40,17 +82,19 @@ struct BlockView {
     hash: String,
     time: String,
     proposer: String,
+    proposer_moniker: Option<String>,
     tx_count: usize,
     tx_hashes: Vec<String>,
 }
 
 impl BlockView {
-    fn from(block: BlockRow, txs: Vec<TxRow>) -> BlockView {
+    fn from(block: BlockRow, txs: Vec<TxRow>, proposer: Option<ValidatorRow>) -> BlockView {
         BlockView {
             height: block.height,
             hash: block.hash,
             time: block.time.to_rfc3339(),
             proposer: block.proposer,
+            proposer_moniker: proposer.and_then(|validator| validator.moniker),
             tx_count: txs.len(),
             tx_hashes: txs.into_iter().map(|tx| tx.hash).collect(),
         }
@@ -61,7 +105,10 @@ async fn query_block_latest(Extension(pool): Extension<SQLitePool>) -> Json<Bloc
     let mut conn = pool.get().unwrap();
     let res = tables::block::top(&mut conn).unwrap();
     let txs = tables::tx::by_block(&mut conn, res.height).unwrap();
-    Json(BlockView::from(res, txs))
+    let proposer = tables::validator::by_consensus_address(&mut conn, &res.proposer)
+        .optional()
+        .unwrap();
+    Json(BlockView::from(res, txs, proposer))
 }
 
 async fn query_block_by_height(
@@ -71,7 +118,23 @@ async fn query_block_by_height(
     let mut conn = pool.get().unwrap();
     let res = tables::block::by_height(&mut conn, height).unwrap();
     let txs = tables::tx::by_block(&mut conn, res.height).unwrap();
-    Json(BlockView::from(res, txs))
+    let proposer = tables::validator::by_consensus_address(&mut conn, &res.proposer)
+        .optional()
+        .unwrap();
+    Json(BlockView::from(res, txs, proposer))
+}
+
+#[derive(Debug, serde::Serialize)]
+struct CoinView {
+    denom: String,
+    amount: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct SignerView {
+    public_key_type: Option<String>,
+    public_key: Option<String>,
+    sequence: u64,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -79,14 +142,51 @@ struct TxView {
     hash: String,
     index: u32,
     block: u64,
+    code: u32,
+    codespace: String,
+    gas_wanted: u64,
+    gas_used: u64,
+    gas_limit: u64,
+    log: String,
+    fee: Vec<CoinView>,
+    payer: Option<String>,
+    granter: Option<String>,
+    memo: String,
+    timeout_height: u64,
+    signers: Vec<SignerView>,
 }
 
-impl Into<TxView> for TxRow {
-    fn into(self) -> TxView {
+impl TxView {
+    fn from(tx: TxRow, fees: Vec<TxFeeRow>, signers: Vec<TxSignerRow>) -> TxView {
         TxView {
-            hash: self.hash,
-            index: self.idx,
-            block: self.block,
+            hash: tx.hash,
+            index: tx.idx,
+            block: tx.block,
+            code: tx.code,
+            codespace: tx.codespace,
+            gas_wanted: tx.gas_wanted,
+            gas_used: tx.gas_used,
+            gas_limit: tx.gas_limit,
+            log: tx.log,
+            fee: fees
+                .into_iter()
+                .map(|fee| CoinView {
+                    denom: fee.denom,
+                    amount: fee.amount.to_string(),
+                })
+                .collect(),
+            payer: tx.payer,
+            granter: tx.granter,
+            memo: tx.memo,
+            timeout_height: tx.timeout_height,
+            signers: signers
+                .into_iter()
+                .map(|signer| SignerView {
+                    public_key_type: signer.public_key_type,
+                    public_key: signer.public_key,
+                    sequence: signer.sequence,
+                })
+                .collect(),
         }
     }
 }
@@ -97,5 +197,726 @@ async fn query_tx_by_hash(
 ) -> Json<TxView> {
     let mut conn = pool.get().unwrap();
     let res = tables::tx::by_hash(&mut conn, &hash).unwrap();
-    Json(res.into())
+    let fees = tables::tx_fee::by_tx(&mut conn, res.block, res.idx).unwrap();
+    let signers = tables::tx_signer::by_tx(&mut conn, res.block, res.idx).unwrap();
+    Json(TxView::from(res, fees, signers))
+}
+
+#[derive(Debug, serde::Serialize)]
+struct AttributeView {
+    key: String,
+    value: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct EventView {
+    phase: String,
+    tx: Option<u32>,
+    index: u32,
+    kind: String,
+    attributes: Vec<AttributeView>,
+}
+
+impl EventView {
+    fn from(events: Vec<EventRow>, attributes: Vec<EventAttributeRow>) -> Vec<EventView> {
+        let mut grouped = HashMap::<(String, Option<u32>, u32), Vec<AttributeView>>::new();
+        for attr in attributes {
+            grouped
+                .entry((attr.phase, attr.tx, attr.event))
+                .or_default()
+                .push(AttributeView {
+                    key: attr.key,
+                    value: attr.value,
+                });
+        }
+
+        events
+            .into_iter()
+            .map(|event| EventView {
+                attributes: grouped
+                    .remove(&(event.phase.clone(), event.tx, event.idx))
+                    .unwrap_or_default(),
+                phase: event.phase,
+                tx: event.tx,
+                index: event.idx,
+                kind: event.kind,
+            })
+            .collect()
+    }
+}
+
+async fn query_block_events(
+    Extension(pool): Extension<SQLitePool>,
+    Path(height): Path<u64>,
+) -> Json<Vec<EventView>> {
+    let mut conn = pool.get().unwrap();
+    let events = tables::event::by_block(&mut conn, height).unwrap();
+    let attributes = tables::event::attributes_by_block(&mut conn, height).unwrap();
+    Json(EventView::from(events, attributes))
+}
+
+async fn query_tx_events(
+    Extension(pool): Extension<SQLitePool>,
+    Path(hash): Path<String>,
+) -> Json<Vec<EventView>> {
+    let mut conn = pool.get().unwrap();
+    let tx = tables::tx::by_hash(&mut conn, &hash).unwrap();
+    let events = tables::event::by_tx(&mut conn, tx.block, tx.idx).unwrap();
+    let attributes = tables::event::attributes_by_tx(&mut conn, tx.block, tx.idx).unwrap();
+    Json(EventView::from(events, attributes))
+}
+
+#[derive(Debug, serde::Serialize)]
+struct MsgView {
+    index: u32,
+    parent: Option<u32>,
+    tag: String,
+    data: Option<serde_json::Value>,
+}
+
+async fn query_tx_msgs(
+    Extension(pool): Extension<SQLitePool>,
+    Extension(registry): Extension<Arc<Registry>>,
+    Path(hash): Path<String>,
+) -> Json<Vec<MsgView>> {
+    let mut conn = pool.get().unwrap();
+    let tx = tables::tx::by_hash(&mut conn, &hash).unwrap();
+    let msgs = tables::msg::by_tx(&mut conn, tx.block, tx.idx).unwrap();
+    Json(
+        msgs.into_iter()
+            .map(|msg| MsgView {
+                index: msg.idx,
+                parent: msg.parent,
+                data: registry.json(&msg.tag, &msg.data).unwrap(),
+                tag: msg.tag,
+            })
+            .collect(),
+    )
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PageParams {
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+impl PageParams {
+    fn limit(&self) -> u32 {
+        std::cmp::min(self.limit.unwrap_or(100), 1000)
+    }
+
+    fn offset(&self) -> u32 {
+        self.offset.unwrap_or(0)
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ProposalView {
+    id: u64,
+    block: u64,
+    version: String,
+    proposer: String,
+    content_type: String,
+    title: String,
+    summary: String,
+}
+
+impl From<ProposalRow> for ProposalView {
+    fn from(proposal: ProposalRow) -> Self {
+        ProposalView {
+            id: proposal.id,
+            block: proposal.block,
+            version: proposal.version,
+            proposer: proposal.proposer,
+            content_type: proposal.content_type,
+            title: proposal.title,
+            summary: proposal.summary,
+        }
+    }
+}
+
+async fn query_proposals(
+    Extension(pool): Extension<SQLitePool>,
+    Query(page): Query<PageParams>,
+) -> Json<Vec<ProposalView>> {
+    let mut conn = pool.get().unwrap();
+    let res = tables::proposal::page(&mut conn, page.limit(), page.offset()).unwrap();
+    Json(res.into_iter().map(ProposalView::from).collect())
+}
+
+#[derive(Debug, serde::Serialize)]
+struct TallyView {
+    proposal_id: u64,
+    voters: u64,
+    options: HashMap<String, f64>,
+}
+
+async fn query_proposal_tally(
+    Extension(pool): Extension<SQLitePool>,
+    Path(id): Path<u64>,
+) -> Json<TallyView> {
+    let mut conn = pool.get().unwrap();
+    let tally = tables::proposal_vote::tally(&mut conn, id).unwrap();
+    let voters = tables::proposal_vote::voters(&mut conn, id).unwrap();
+    Json(TallyView {
+        proposal_id: id,
+        voters,
+        options: tally.into_iter().map(|it| (it.option, it.weight)).collect(),
+    })
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ExecutionView {
+    block: u64,
+    tx: u32,
+    msg: u32,
+    sender: String,
+    payload: serde_json::Value,
+}
+
+impl From<ContractExecutionRow> for ExecutionView {
+    fn from(execution: ContractExecutionRow) -> Self {
+        ExecutionView {
+            block: execution.block,
+            tx: execution.tx,
+            msg: execution.msg,
+            sender: execution.sender,
+            payload: serde_json::from_str(&execution.payload)
+                .unwrap_or(serde_json::Value::String(execution.payload)),
+        }
+    }
+}
+
+async fn query_contract_executions(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+    Query(page): Query<PageParams>,
+) -> Json<Vec<ExecutionView>> {
+    let mut conn = pool.get().unwrap();
+    let res = tables::contract_execution::page_by_contract(
+        &mut conn,
+        &address,
+        page.limit(),
+        page.offset(),
+    )
+    .unwrap();
+    Json(res.into_iter().map(ExecutionView::from).collect())
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ValidatorEditView {
+    block: u64,
+    tx: u32,
+    msg: u32,
+    kind: String,
+    moniker: Option<String>,
+    identity: Option<String>,
+    website: Option<String>,
+    security_contact: Option<String>,
+    details: Option<String>,
+    commission_rate: Option<String>,
+    min_self_delegation: Option<String>,
+}
+
+impl From<ValidatorHistoryRow> for ValidatorEditView {
+    fn from(edit: ValidatorHistoryRow) -> Self {
+        ValidatorEditView {
+            block: edit.block,
+            tx: edit.tx,
+            msg: edit.msg,
+            kind: edit.kind,
+            moniker: edit.moniker,
+            identity: edit.identity,
+            website: edit.website,
+            security_contact: edit.security_contact,
+            details: edit.details,
+            commission_rate: edit.commission_rate,
+            min_self_delegation: edit.min_self_delegation,
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ValidatorView {
+    operator_address: String,
+    consensus_pubkey: Option<String>,
+    consensus_address: Option<String>,
+    moniker: Option<String>,
+    identity: Option<String>,
+    website: Option<String>,
+    security_contact: Option<String>,
+    details: Option<String>,
+    commission_rate: Option<String>,
+    commission_max_rate: Option<String>,
+    commission_max_change_rate: Option<String>,
+    min_self_delegation: Option<String>,
+    created: Option<u64>,
+    updated: u64,
+    history: Vec<ValidatorEditView>,
+}
+
+impl ValidatorView {
+    fn from(validator: ValidatorRow, history: Vec<ValidatorHistoryRow>) -> ValidatorView {
+        ValidatorView {
+            operator_address: validator.operator_address,
+            consensus_pubkey: validator.consensus_pubkey,
+            consensus_address: validator.consensus_address,
+            moniker: validator.moniker,
+            identity: validator.identity,
+            website: validator.website,
+            security_contact: validator.security_contact,
+            details: validator.details,
+            commission_rate: validator.commission_rate,
+            commission_max_rate: validator.commission_max_rate,
+            commission_max_change_rate: validator.commission_max_change_rate,
+            min_self_delegation: validator.min_self_delegation,
+            created: validator.created,
+            updated: validator.updated,
+            history: history.into_iter().map(ValidatorEditView::from).collect(),
+        }
+    }
+}
+
+/// Looks the validator up by operator address, or by the hex consensus address blocks refer to
+async fn query_validator(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+) -> Json<ValidatorView> {
+    let mut conn = pool.get().unwrap();
+    let validator = match tables::validator::by_operator_address(&mut conn, &address).optional() {
+        Ok(Some(validator)) => validator,
+        _ => tables::validator::by_consensus_address(&mut conn, &address.to_uppercase()).unwrap(),
+    };
+    let history =
+        tables::validator_history::by_operator_address(&mut conn, &validator.operator_address)
+            .unwrap();
+    Json(ValidatorView::from(validator, history))
+}
+
+/// Consensus address of an operator address, anything else is taken as a consensus address
+fn consensus_address(
+    conn: &mut r2d2::PooledConnection<SqliteConnectionManager>,
+    address: &str,
+) -> String {
+    tables::validator::by_operator_address(conn, address)
+        .optional()
+        .unwrap()
+        .and_then(|validator| validator.consensus_address)
+        .unwrap_or_else(|| address.to_uppercase())
+}
+
+/// Rejected query parameters
+type BadRequest = (http::StatusCode, String);
+
+fn bad_request(msg: String) -> BadRequest {
+    (http::StatusCode::BAD_REQUEST, msg)
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RangeParams {
+    from: Option<u64>,
+    to: Option<u64>,
+    min_length: Option<u64>,
+    interval: Option<u64>,
+}
+
+impl RangeParams {
+    /// Defaults to the last 10000 indexed blocks
+    fn bounds(&self, conn: &mut r2d2::PooledConnection<SqliteConnectionManager>) -> (u64, u64) {
+        let to = self
+            .to
+            .unwrap_or_else(|| tables::block::top(conn).unwrap().height);
+        let from = self.from.unwrap_or_else(|| to.saturating_sub(9999));
+        (from, to)
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct UptimeView {
+    address: String,
+    from: u64,
+    to: u64,
+    blocks: u64,
+    signed: u64,
+    nil: u64,
+    absent: u64,
+    uptime: f64,
+}
+
+/// Nil votes count as signed, as they do for slashing
+async fn query_validator_uptime(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+    Query(range): Query<RangeParams>,
+) -> Json<UptimeView> {
+    let mut conn = pool.get().unwrap();
+    let address = consensus_address(&mut conn, &address);
+    let (from, to) = range.bounds(&mut conn);
+    let res = tables::commit_signature::uptime(&mut conn, &address, from, to).unwrap();
+    let uptime = if res.blocks == 0 {
+        0.0
+    } else {
+        (res.blocks - res.absent) as f64 / res.blocks as f64
+    };
+    Json(UptimeView {
+        address,
+        from,
+        to,
+        blocks: res.blocks,
+        signed: res.signed,
+        nil: res.nil,
+        absent: res.absent,
+        uptime,
+    })
+}
+
+#[derive(Debug, serde::Serialize)]
+struct StreakView {
+    start: u64,
+    end: u64,
+    length: u64,
+}
+
+async fn query_validator_missed(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+    Query(range): Query<RangeParams>,
+) -> Json<Vec<StreakView>> {
+    let mut conn = pool.get().unwrap();
+    let address = consensus_address(&mut conn, &address);
+    let (from, to) = range.bounds(&mut conn);
+    let min_length = range.min_length.unwrap_or(1);
+    let res = tables::commit_signature::missed_streaks(&mut conn, &address, from, to, min_length)
+        .unwrap();
+    Json(
+        res.into_iter()
+            .map(|it| StreakView {
+                start: it.start,
+                end: it.end,
+                length: it.length,
+            })
+            .collect(),
+    )
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DelegationFlowView {
+    from: u64,
+    to: u64,
+    denom: String,
+    delegated: String,
+    undelegated: String,
+    net: String,
+}
+
+/// Net delegation flow of a validator, bucketed by `interval` blocks
+async fn query_validator_delegations(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+    Query(range): Query<RangeParams>,
+) -> Result<Json<Vec<DelegationFlowView>>, BadRequest> {
+    let interval = range.interval.unwrap_or(1000);
+    if interval == 0 {
+        return Err(bad_request("The interval must be at least 1".to_string()));
+    }
+    let mut conn = pool.get().unwrap();
+    let (from, to) = range.bounds(&mut conn);
+    let changes = tables::delegation_change::by_validator(&mut conn, &address, from, to).unwrap();
+
+    let mut buckets = BTreeMap::<(u64, String), (i128, i128)>::new();
+    for change in changes {
+        let start = from + (change.block - from) / interval * interval;
+        let (delegated, undelegated) = buckets.entry((start, change.denom)).or_default();
+        if change.amount > 0 {
+            *delegated += change.amount;
+        } else {
+            *undelegated -= change.amount;
+        }
+    }
+
+    Ok(Json(
+        buckets
+            .into_iter()
+            .map(
+                |((start, denom), (delegated, undelegated))| DelegationFlowView {
+                    from: start,
+                    to: std::cmp::min(start.saturating_add(interval - 1), to),
+                    denom,
+                    delegated: delegated.to_string(),
+                    undelegated: undelegated.to_string(),
+                    net: (delegated - undelegated).to_string(),
+                },
+            )
+            .collect(),
+    ))
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DelegationView {
+    validator: String,
+    denom: String,
+    amount: String,
+}
+
+/// Delegations as reconstructed from the imported genesis and the indexed range,
+/// slashing is not accounted for
+async fn query_address_delegations(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+) -> Json<Vec<DelegationView>> {
+    let mut conn = pool.get().unwrap();
+    let initial = tables::genesis::delegations_by_delegator(&mut conn, &address).unwrap();
+    let changes = tables::delegation_change::by_delegator(&mut conn, &address).unwrap();
+
+    let mut delegations = BTreeMap::<(String, String), i128>::new();
+    for delegation in initial {
+        *delegations
+            .entry((delegation.validator, delegation.denom))
+            .or_default() += delegation.amount as i128;
+    }
+    for change in changes {
+        *delegations
+            .entry((change.validator, change.denom))
+            .or_default() += change.amount;
+    }
+
+    Json(
+        delegations
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|((validator, denom), amount)| DelegationView {
+                validator,
+                denom,
+                amount: amount.to_string(),
+            })
+            .collect(),
+    )
+}
+
+/// RFC 3339 timestamps, the whole history by default
+#[derive(Debug, serde::Deserialize)]
+struct TimeRangeParams {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+impl TimeRangeParams {
+    fn bounds(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), BadRequest> {
+        let parse = |it: &String| {
+            DateTime::parse_from_rfc3339(it)
+                .map(|it| it.with_timezone(&Utc))
+                .map_err(|err| bad_request(format!("Invalid timestamp {} : {}", it, err)))
+        };
+        let from = self.from.as_ref().map(parse).transpose()?;
+        let to = self.to.as_ref().map(parse).transpose()?;
+        Ok((
+            from.unwrap_or_else(|| Utc.timestamp(0, 0)),
+            to.unwrap_or_else(Utc::now),
+        ))
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct RewardsView {
+    address: String,
+    from: String,
+    to: String,
+    rewards: Vec<CoinView>,
+    commission: Vec<CoinView>,
+}
+
+fn coin_views(totals: BTreeMap<String, u128>) -> Vec<CoinView> {
+    totals
+        .into_iter()
+        .map(|(denom, amount)| CoinView {
+            denom,
+            amount: amount.to_string(),
+        })
+        .collect()
+}
+
+async fn query_address_rewards(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+    Query(range): Query<TimeRangeParams>,
+) -> Result<Json<RewardsView>, BadRequest> {
+    let (from, to) = range.bounds()?;
+    let mut conn = pool.get().unwrap();
+    let withdrawals =
+        tables::reward_withdrawal::by_address_and_time(&mut conn, &address, from, to).unwrap();
+
+    let mut rewards = BTreeMap::<String, u128>::new();
+    let mut commission = BTreeMap::<String, u128>::new();
+    for withdrawal in withdrawals {
+        let totals = if withdrawal.kind == model::WithdrawalKind::Commission.as_str() {
+            &mut commission
+        } else {
+            &mut rewards
+        };
+        *totals.entry(withdrawal.denom).or_default() += withdrawal.amount;
+    }
+
+    Ok(Json(RewardsView {
+        address,
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        rewards: coin_views(rewards),
+        commission: coin_views(commission),
+    }))
+}
+
+#[derive(Debug, serde::Serialize)]
+struct LedgerEntryView {
+    block: u64,
+    tx: Option<u32>,
+    msg: Option<u32>,
+    kind: String,
+    counterparty: String,
+    denom: String,
+    debit: String,
+    credit: String,
+}
+
+impl From<LedgerEntryRow> for LedgerEntryView {
+    fn from(entry: LedgerEntryRow) -> Self {
+        LedgerEntryView {
+            block: entry.block,
+            tx: entry.tx,
+            msg: entry.msg,
+            kind: entry.kind,
+            counterparty: entry.counterparty,
+            denom: entry.denom,
+            debit: entry.debit.to_string(),
+            credit: entry.credit.to_string(),
+        }
+    }
+}
+
+async fn query_address_ledger(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+    Query(page): Query<PageParams>,
+) -> Json<Vec<LedgerEntryView>> {
+    let mut conn = pool.get().unwrap();
+    let res =
+        tables::ledger_entry::page_by_address(&mut conn, &address, page.limit(), page.offset())
+            .unwrap();
+    Json(res.into_iter().map(LedgerEntryView::from).collect())
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HeightParams {
+    height: Option<u64>,
+}
+
+impl HeightParams {
+    /// Defaults to, and never goes past, the last block processed by the ledger indexer
+    fn height(&self, conn: &mut r2d2::PooledConnection<SqliteConnectionManager>) -> u64 {
+        let top = tables::cursor::get(conn, indexer::ledger::CURSOR)
+            .unwrap()
+            .unwrap_or(0);
+        self.height.map(|it| std::cmp::min(it, top)).unwrap_or(top)
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct BalancesView {
+    address: String,
+    height: u64,
+    balances: Vec<CoinView>,
+}
+
+/// Latest checkpoint at or below the height plus the ledger entries since
+async fn query_address_balances(
+    Extension(pool): Extension<SQLitePool>,
+    Path(address): Path<String>,
+    Query(params): Query<HeightParams>,
+) -> Json<BalancesView> {
+    let mut conn = pool.get().unwrap();
+    let height = params.height(&mut conn);
+
+    let checkpoints = tables::balance::checkpoints_by_address(&mut conn, &address, height).unwrap();
+    let from = checkpoints.iter().map(|it| it.block).min();
+
+    let mut balances = BTreeMap::<String, (Option<u64>, i128)>::new();
+    for checkpoint in checkpoints {
+        balances.insert(
+            checkpoint.denom,
+            (Some(checkpoint.block), checkpoint.amount),
+        );
+    }
+
+    for entry in
+        tables::ledger_entry::by_address_between(&mut conn, &address, from, height).unwrap()
+    {
+        let (block, amount) = balances.entry(entry.denom).or_insert((None, 0));
+        if block.map(|it| entry.block > it).unwrap_or(true) {
+            *amount += entry.credit as i128 - entry.debit as i128;
+        }
+    }
+
+    Json(BalancesView {
+        address,
+        height,
+        balances: balances
+            .into_iter()
+            .filter(|(_, (_, amount))| *amount != 0)
+            .map(|(denom, (_, amount))| CoinView {
+                denom,
+                amount: amount.to_string(),
+            })
+            .collect(),
+    })
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HoldersParams {
+    denom: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct HolderView {
+    address: String,
+    amount: String,
+}
+
+/// Rich list of a denom, holders are sorted by decreasing balance
+async fn query_holders(
+    Extension(pool): Extension<SQLitePool>,
+    Query(holders): Query<HoldersParams>,
+    Query(params): Query<HeightParams>,
+    Query(page): Query<PageParams>,
+) -> Json<Vec<HolderView>> {
+    let mut conn = pool.get().unwrap();
+    let height = params.height(&mut conn);
+    let denom = &holders.denom;
+
+    let mut balances = HashMap::<String, i128>::new();
+    for checkpoint in tables::balance::checkpoints_by_denom(&mut conn, denom, height).unwrap() {
+        balances.insert(checkpoint.address, checkpoint.amount);
+    }
+
+    // Every balance changed up to the last checkpoint is materialized in it
+    let from = tables::balance::last_checkpoint(&mut conn, height).unwrap();
+    for entry in tables::ledger_entry::by_denom_between(&mut conn, denom, from, height).unwrap() {
+        *balances.entry(entry.address).or_default() += entry.credit as i128 - entry.debit as i128;
+    }
+
+    let mut balances: Vec<(String, i128)> = balances
+        .into_iter()
+        .filter(|(_, amount)| *amount > 0)
+        .collect();
+    balances.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Json(
+        balances
+            .into_iter()
+            .skip(page.offset() as usize)
+            .take(page.limit() as usize)
+            .map(|(address, amount)| HolderView {
+                address,
+                amount: amount.to_string(),
+            })
+            .collect(),
+    )
 }
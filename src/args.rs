@@ -1,3 +1,4 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use clap;
@@ -15,8 +16,49 @@ pub struct Args {
     #[clap(long, short, default_value = "./data")]
     pub datadir: PathBuf,
 
-    #[clap(long, default_value = "https://rpc.atomscan.com")]
-    pub rpc: String,
+    /// RPC endpoints, repeated or comma separated, the next one is tried whenever a request fails
+    #[clap(
+        long,
+        default_value = "https://rpc.atomscan.com",
+        multiple_occurrences = true,
+        use_delimiter = true
+    )]
+    pub rpc: Vec<String>,
+
+    /// RPC requests per second, shared by all requests, zero to disable limiting
+    #[clap(long, default_value_t = 10.0)]
+    pub rpc_rate: f64,
+
+    /// RPC requests allowed at once before the rate applies
+    #[clap(long, default_value_t = 20)]
+    pub rpc_burst: u32,
+
+    /// Seconds before an RPC request is abandoned
+    #[clap(long, default_value_t = 30)]
+    pub rpc_timeout: u64,
+
+    /// Retries of a failed RPC request before giving up on the block
+    #[clap(long, default_value_t = 5)]
+    pub rpc_retries: u32,
+
+    /// Milliseconds before the first retry, doubled on each following one
+    #[clap(long, default_value_t = 500)]
+    pub rpc_backoff: u64,
+
+    /// Reads recorded RPC responses from this directory rather than from the RPC endpoints
+    #[clap(long)]
+    pub source_dir: Option<PathBuf>,
+
+    /// Records the RPC responses of `from_block..=to_block` into this directory, then exits
+    #[clap(long)]
+    pub record: Option<PathBuf>,
+
+    /// Serves the RPC responses recorded in this directory, as a stand-in for a node
+    #[clap(long)]
+    pub replay: Option<PathBuf>,
+
+    #[clap(long, default_value = "127.0.0.1:26657")]
+    pub replay_listen: SocketAddr,
 
     #[clap(long, default_value_t = 5200791)]
     pub from_block: u32,
@@ -26,4 +68,50 @@ pub struct Args {
 
     #[clap(long)]
     pub index: bool,
+
+    /// Polls for new blocks every second rather than following them over WebSocket
+    #[clap(long)]
+    pub poll: bool,
+
+    /// Blocks fetched and decoded ahead of the one being written
+    #[clap(long, default_value_t = 8)]
+    pub fetch_concurrency: usize,
+
+    /// Keeps the raw txs and block_results of each block, zstd compressed, to decode them again
+    /// without the RPC
+    #[clap(long)]
+    pub archive: bool,
+
+    /// Blocks between two validator set snapshots, a snapshot is also taken whenever the set changes
+    #[clap(long, default_value_t = 1000)]
+    pub validator_set_interval: u64,
+
+    /// Blocks between two materializations of the balances that changed
+    #[clap(long, default_value_t = 10000)]
+    pub balance_checkpoint_interval: u64,
+
+    /// Rebuilds the decoded tables of the indexed blocks from this height onward,
+    /// derived indexers are rewound to it and catch up once indexing resumes.
+    /// The datadir is locked meanwhile, it cannot run alongside `--index` in another process.
+    /// Exits once done, like `--import-genesis` and `--record`
+    #[clap(long)]
+    pub reindex_from: Option<u64>,
+
+    /// Last block rebuilt by the reindex, the latest indexed one by default
+    #[clap(long)]
+    pub reindex_to: Option<u64>,
+
+    /// Tables rebuilt by the reindex : `address_msg` from the stored messages,
+    /// `tx`, `msg` and `event` from the archive
+    #[clap(
+        long,
+        default_value = "address_msg",
+        multiple_occurrences = true,
+        use_delimiter = true
+    )]
+    pub reindex_tables: Vec<String>,
+
+    /// Imports a genesis file into the genesis tables, then exits. Balances are seeded from it
+    #[clap(long)]
+    pub import_genesis: Option<PathBuf>,
 }
@@ -0,0 +1,575 @@
+use async_trait::async_trait;
+use cosmrs::rpc::endpoint::{block, block_results, validators};
+use cosmrs::rpc::{self, Response as _, SimpleRequest};
+use futures::{SinkExt, StreamExt};
+use hyper::client::HttpConnector;
+use hyper::{header, Body, StatusCode, Uri};
+use hyper_rustls::HttpsConnector;
+use rand::Rng;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, timeout, Duration, Instant};
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::args::Args;
+use crate::fetch;
+use crate::model;
+use crate::source::BlockSource;
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+const VALIDATORS_PER_PAGE: u8 = 100;
+
+const NEW_BLOCK_QUERY: &str = "tm.event='NewBlock'";
+
+/// Successful responses longer than this are results, they are not searched for rate limiting
+const RATE_LIMIT_BODY_MAX: usize = 4096;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The endpoint URL could not be parsed
+    InvalidUrl(String),
+    /// No endpoint was given
+    NoEndpoint,
+    /// The request could not be built
+    Request(hyper::http::Error),
+    /// The endpoint could not be reached
+    Http(hyper::Error),
+    /// The endpoint answered with an HTTP error status
+    Status(StatusCode),
+    /// The endpoint answered 429, along with the delay it asked for
+    RateLimited(Option<Duration>),
+    /// The endpoint answered with a JSON-RPC error or an unparsable body
+    Rpc(Box<rpc::Error>),
+    /// The endpoint did not answer within the request timeout
+    Timeout(Duration),
+    /// The response could not be turned into the model
+    Decode(cosmrs::ErrorReport),
+    /// The WebSocket of the endpoint could not be reached or dropped
+    WebSocket(Box<tungstenite::Error>),
+    /// A recorded response could not be read
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidUrl(url) => write!(f, "Invalid URL : {}", url),
+            Error::NoEndpoint => write!(f, "No RPC endpoint"),
+            Error::Request(err) => write!(f, "Invalid request : {}", err),
+            Error::Http(err) => write!(f, "HTTP error : {}", err),
+            Error::Status(status) => write!(f, "HTTP status : {}", status),
+            Error::RateLimited(_) => write!(f, "Rate limited"),
+            Error::Rpc(err) => write!(f, "RPC error : {}", err.detail()),
+            Error::Timeout(duration) => write!(f, "Timed out after {:?}", duration),
+            Error::Decode(err) => write!(f, "Decoding error : {}", err),
+            Error::WebSocket(err) => write!(f, "WebSocket error : {}", err),
+            Error::Io(err) => write!(f, "IO error : {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// Fraction of the configured rate currently allowed, lowered on each 429
+    factor: f64,
+    paused_until: Option<Instant>,
+}
+
+/// Token bucket shared by every request, refilled at `rate` tokens per second up to `burst`.
+/// A 429 pauses all requests and halves the rate, which then slowly recovers.
+struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+    throttled: AtomicU64,
+}
+
+impl RateLimiter {
+    const MIN_FACTOR: f64 = 1.0 / 16.0;
+
+    fn new(rate: f64, burst: u32) -> Self {
+        let burst = std::cmp::max(burst, 1) as f64;
+        RateLimiter {
+            rate,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                updated: Instant::now(),
+                factor: 1.0,
+                paused_until: None,
+            }),
+            throttled: AtomicU64::new(0),
+        }
+    }
+
+    /// Waits for a token, a rate of zero disables limiting
+    async fn acquire(&self) {
+        if self.rate <= 0.0 {
+            return;
+        }
+
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+                let rate = self.rate * bucket.factor;
+
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * rate).min(self.burst);
+                bucket.updated = now;
+
+                match bucket.paused_until.filter(|until| *until > now) {
+                    Some(until) => until - now,
+                    None if bucket.tokens >= 1.0 => {
+                        bucket.tokens -= 1.0;
+                        return;
+                    }
+                    None => Duration::from_secs_f64((1.0 - bucket.tokens) / rate),
+                }
+            };
+
+            self.throttled
+                .fetch_add(wait.as_millis() as u64, Ordering::Relaxed);
+            sleep(wait).await
+        }
+    }
+
+    /// Returns the lowered rate
+    async fn penalize(&self, pause: Duration) -> f64 {
+        let mut bucket = self.bucket.lock().await;
+        bucket.factor = (bucket.factor / 2.0).max(Self::MIN_FACTOR);
+        bucket.tokens = 0.0;
+        bucket.paused_until = Some(Instant::now() + pause);
+        self.rate * bucket.factor
+    }
+
+    async fn recover(&self) {
+        let mut bucket = self.bucket.lock().await;
+        bucket.factor = (bucket.factor * 1.01).min(1.0);
+    }
+
+    fn throttled(&self) -> Duration {
+        Duration::from_millis(self.throttled.load(Ordering::Relaxed))
+    }
+}
+
+/// Some providers report rate limiting in the body only, as a JSON-RPC error or as plain text
+fn is_rate_limit_body(status: StatusCode, body: &[u8]) -> bool {
+    if status.is_success() && body.len() > RATE_LIMIT_BODY_MAX {
+        return false;
+    }
+    let text = match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(json) => match json.get("error") {
+            Some(error) if error["code"] == 429 => return true,
+            Some(error) => error.to_string(),
+            None => return false,
+        },
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    };
+    let text = text.to_lowercase();
+    text.contains("rate limit") || text.contains("too many requests")
+}
+
+struct Endpoint {
+    url: String,
+    uri: Uri,
+    http: hyper::Client<HttpsConnector<HttpConnector>>,
+}
+
+impl Endpoint {
+    fn new(url: &str) -> Result<Self> {
+        Ok(Endpoint {
+            url: url.to_string(),
+            uri: url
+                .parse()
+                .map_err(|_| Error::InvalidUrl(url.to_string()))?,
+            http: hyper::Client::builder().build(HttpsConnector::with_native_roots()),
+        })
+    }
+
+    /// Same as the tendermint-rpc HTTP transport, but with access to the status code
+    async fn perform<R: SimpleRequest>(&self, request: R) -> Result<R::Response> {
+        let request = hyper::Request::post(&self.uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(request.into_json()))
+            .map_err(Error::Request)?;
+
+        let response = self.http.request(request).await.map_err(Error::Http)?;
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|it| it.to_str().ok())
+                .and_then(|it| it.parse().ok())
+                .map(Duration::from_secs);
+            return Err(Error::RateLimited(retry_after));
+        }
+
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(Error::Http)?;
+        if is_rate_limit_body(status, &body) {
+            return Err(Error::RateLimited(None));
+        }
+        if !status.is_success() {
+            return Err(Error::Status(status));
+        }
+        R::Response::from_string(body).map_err(|err| Error::Rpc(Box::new(err)))
+    }
+
+    /// `http://host:26657/` is served over WebSocket at `ws://host:26657/websocket`
+    fn websocket_url(&self) -> String {
+        let url = self.url.trim_end_matches('/');
+        let url = match url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{}", rest),
+            Some((_, rest)) => format!("ws://{}", rest),
+            None => format!("ws://{}", url),
+        };
+        format!("{}/websocket", url)
+    }
+}
+
+/// Heights of the blocks announced by a `NewBlock` subscription
+pub struct NewBlocks {
+    url: String,
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl NewBlocks {
+    async fn subscribe(url: String) -> Result<Self> {
+        let (mut socket, _) = tokio_tungstenite::connect_async(url.as_str())
+            .await
+            .map_err(|err| Error::WebSocket(Box::new(err)))?;
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "method": "subscribe",
+            "params": { "query": NEW_BLOCK_QUERY },
+        });
+        socket
+            .send(Message::Text(request.to_string()))
+            .await
+            .map_err(|err| Error::WebSocket(Box::new(err)))?;
+
+        Ok(NewBlocks { url, socket })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The next announced height, `None` once the connection is closed
+    pub async fn next(&mut self) -> Option<u64> {
+        while let Some(message) = self.socket.next().await {
+            let text = match message {
+                Ok(Message::Text(text)) => text,
+                Ok(Message::Close(_)) => return None,
+                Ok(_) => continue,
+                Err(err) => {
+                    log::warn!("WebSocket of {} dropped : {}", self.url, err);
+                    return None;
+                }
+            };
+
+            // The subscription is acknowledged with an empty result, events carry a block
+            let event: serde_json::Value = match serde_json::from_str(&text) {
+                Ok(event) => event,
+                Err(_) => continue,
+            };
+            if let Some(err) = event.get("error") {
+                log::warn!("Subscription on {} failed : {}", self.url, err);
+                return None;
+            }
+            let height = event
+                .pointer("/result/data/value/block/header/height")
+                .and_then(|it| it.as_str())
+                .and_then(|it| it.parse().ok());
+            if height.is_some() {
+                return height;
+            }
+        }
+        None
+    }
+}
+
+/// Block fetching over a list of RPC endpoints.
+/// Failed requests are retried with exponential backoff and jitter, moving on to the next
+/// endpoint each time, the endpoint that last answered is kept for the following requests.
+pub struct Client {
+    endpoints: Vec<Endpoint>,
+    current: AtomicUsize,
+    limiter: RateLimiter,
+    timeout: Duration,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl Client {
+    pub fn new(args: &Args) -> Result<Self> {
+        let endpoints = args
+            .rpc
+            .iter()
+            .map(|url| Endpoint::new(url))
+            .collect::<Result<Vec<_>>>()?;
+        // Endpoints are picked modulo their count
+        if endpoints.is_empty() {
+            return Err(Error::NoEndpoint);
+        }
+
+        Ok(Client {
+            endpoints,
+            current: AtomicUsize::new(0),
+            limiter: RateLimiter::new(args.rpc_rate, args.rpc_burst),
+            timeout: Duration::from_secs(args.rpc_timeout),
+            retries: args.rpc_retries,
+            backoff: Duration::from_millis(args.rpc_backoff),
+        })
+    }
+
+    /// Full jitter, anywhere between half and all of the exponential delay
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF);
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+
+    async fn call<'a, T, F, Fut>(&'a self, name: &str, request: F) -> Result<T>
+    where
+        F: Fn(&'a Endpoint) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let i = self.current.load(Ordering::Relaxed) % self.endpoints.len();
+            let endpoint = &self.endpoints[i];
+
+            self.limiter.acquire().await;
+            let err = match timeout(self.timeout, request(endpoint)).await {
+                Ok(Ok(res)) => {
+                    self.limiter.recover().await;
+                    return Ok(res);
+                }
+                Ok(Err(err)) => err,
+                Err(_) => Error::Timeout(self.timeout),
+            };
+
+            // Even out of retries, the other requests sharing the limiter slow down
+            let mut delay = self.backoff(attempt);
+            if let Error::RateLimited(retry_after) = &err {
+                delay = retry_after.unwrap_or(delay);
+                let rate = self.limiter.penalize(delay).await;
+                log::warn!(
+                    "Rate limited by {}, pausing for {:?} and lowering the rate to {:.2} requests per second",
+                    endpoint.url,
+                    delay,
+                    rate
+                );
+            }
+
+            if attempt >= self.retries {
+                return Err(err);
+            }
+
+            if !matches!(err, Error::RateLimited(_)) {
+                log::warn!(
+                    "{} failed on {} (attempt {}/{}), retrying in {:?} : {}",
+                    name,
+                    endpoint.url,
+                    attempt + 1,
+                    self.retries + 1,
+                    delay,
+                    err
+                );
+            }
+
+            let next = (i + 1) % self.endpoints.len();
+            let _ = self
+                .current
+                .compare_exchange(i, next, Ordering::Relaxed, Ordering::Relaxed);
+
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Every page of the validator set of a height, as a single response
+    pub async fn validators(&self, height: u64) -> Result<validators::Response> {
+        let height = (height as u32).into();
+        let mut validators = Vec::new();
+        let mut page: usize = 1;
+
+        loop {
+            let res = self
+                .call("validators", |endpoint| {
+                    endpoint.perform(validators::Request::new(
+                        Some(height),
+                        Some(page.into()),
+                        Some(VALIDATORS_PER_PAGE.into()),
+                    ))
+                })
+                .await?;
+
+            let last = res.validators.is_empty();
+            validators.extend(res.validators);
+            if last || validators.len() as i32 >= res.total {
+                return Ok(validators::Response::new(
+                    res.block_height,
+                    validators,
+                    res.total,
+                ));
+            }
+            page += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl BlockSource for Client {
+    /// Subscribes to new blocks on the endpoint currently in use
+    async fn new_blocks(&self) -> Option<Result<NewBlocks>> {
+        let i = self.current.load(Ordering::Relaxed) % self.endpoints.len();
+        let url = self.endpoints[i].websocket_url();
+        Some(
+            match timeout(self.timeout, NewBlocks::subscribe(url)).await {
+                Ok(res) => res,
+                Err(_) => Err(Error::Timeout(self.timeout)),
+            },
+        )
+    }
+
+    fn throttled(&self) -> Duration {
+        self.limiter.throttled()
+    }
+
+    async fn latest_height(&self) -> Result<u64> {
+        self.call("latest_block", |endpoint| {
+            endpoint.perform(block::Request::default())
+        })
+        .await
+        .map(|res| res.block.header.height.value())
+    }
+
+    async fn raw_block(&self, height: u64) -> Result<(block::Response, block_results::Response)> {
+        let height = (height as u32).into();
+        let res = self
+            .call("block", |endpoint| {
+                endpoint.perform(block::Request::new(height))
+            })
+            .await?;
+        let results = self
+            .call("block_results", |endpoint| {
+                endpoint.perform(block_results::Request::new(height))
+            })
+            .await?;
+        Ok((res, results))
+    }
+
+    async fn validator_set(&self, height: u64) -> Result<model::ValidatorSet> {
+        let res = self.validators(height).await?;
+        Ok(fetch::validator_set_to_model(&res))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn factor(limiter: &RateLimiter) -> f64 {
+        limiter.bucket.lock().await.factor
+    }
+
+    #[tokio::test]
+    async fn penalize_halves_the_rate_down_to_a_floor() {
+        let limiter = RateLimiter::new(16.0, 4);
+
+        assert_eq!(limiter.penalize(Duration::ZERO).await, 8.0);
+        assert_eq!(limiter.penalize(Duration::ZERO).await, 4.0);
+        for _ in 0..8 {
+            limiter.penalize(Duration::ZERO).await;
+        }
+        assert_eq!(factor(&limiter).await, RateLimiter::MIN_FACTOR);
+        assert_eq!(limiter.penalize(Duration::ZERO).await, 1.0);
+    }
+
+    #[tokio::test]
+    async fn recover_raises_the_rate_up_to_the_configured_one() {
+        let limiter = RateLimiter::new(16.0, 4);
+        limiter.penalize(Duration::ZERO).await;
+
+        limiter.recover().await;
+        assert_eq!(factor(&limiter).await, 0.5 * 1.01);
+
+        for _ in 0..100 {
+            limiter.recover().await;
+        }
+        assert_eq!(factor(&limiter).await, 1.0);
+    }
+
+    #[tokio::test]
+    async fn penalize_pauses_every_request() {
+        let limiter = RateLimiter::new(1000.0, 4);
+        let pause = Duration::from_millis(50);
+        limiter.penalize(pause).await;
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() >= pause);
+        assert!(limiter.throttled() > Duration::ZERO);
+    }
+
+    #[test]
+    fn rejects_an_empty_endpoint_list() {
+        use clap::Parser;
+
+        let mut args = Args::parse_from(["quadrant"]);
+        args.rpc.clear();
+        assert!(matches!(Client::new(&args), Err(Error::NoEndpoint)));
+    }
+
+    #[test]
+    fn matches_rate_limit_bodies() {
+        assert!(is_rate_limit_body(
+            StatusCode::OK,
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":429,"message":"busy"}}"#
+        ));
+        assert!(is_rate_limit_body(
+            StatusCode::OK,
+            br#"{"error":{"code":-32000,"message":"Rate limit exceeded"}}"#
+        ));
+        assert!(is_rate_limit_body(StatusCode::OK, b"Too Many Requests"));
+
+        assert!(!is_rate_limit_body(
+            StatusCode::OK,
+            br#"{"jsonrpc":"2.0","id":1,"result":{}}"#
+        ));
+        assert!(!is_rate_limit_body(
+            StatusCode::OK,
+            br#"{"error":{"code":-32603,"message":"height is not available"}}"#
+        ));
+        assert!(!is_rate_limit_body(StatusCode::OK, b"Bad Gateway"));
+
+        // Large successful results are not searched, unlike failed responses
+        let large = format!(
+            "{{\"result\":\"{}\"}} rate limit",
+            "a".repeat(RATE_LIMIT_BODY_MAX)
+        );
+        assert!(!is_rate_limit_body(StatusCode::OK, large.as_bytes()));
+        assert!(is_rate_limit_body(
+            StatusCode::SERVICE_UNAVAILABLE,
+            large.as_bytes()
+        ));
+    }
+}
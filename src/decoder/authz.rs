@@ -0,0 +1,212 @@
+use chrono::{TimeZone, Utc};
+use cosmrs::Any;
+use prost::Message;
+use serde_json::{json, Value};
+
+use crate::decoder::{address, coins_json, MsgDecoder, Registry};
+use crate::model;
+
+/// cosmos-sdk-proto 0.9 does not ship authz, these mirror cosmos/authz/v1beta1
+pub mod v1beta1 {
+    use cosmrs::proto::cosmos::base::v1beta1::Coin;
+    use cosmrs::proto::tendermint::google::protobuf::Timestamp;
+    use cosmrs::Any;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgExec {
+        #[prost(string, tag = "1")]
+        pub grantee: String,
+        #[prost(message, repeated, tag = "2")]
+        pub msgs: Vec<Any>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Grant {
+        #[prost(message, optional, tag = "1")]
+        pub authorization: Option<Any>,
+        #[prost(message, optional, tag = "2")]
+        pub expiration: Option<Timestamp>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgGrant {
+        #[prost(string, tag = "1")]
+        pub granter: String,
+        #[prost(string, tag = "2")]
+        pub grantee: String,
+        #[prost(message, optional, tag = "3")]
+        pub grant: Option<Grant>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgRevoke {
+        #[prost(string, tag = "1")]
+        pub granter: String,
+        #[prost(string, tag = "2")]
+        pub grantee: String,
+        #[prost(string, tag = "3")]
+        pub msg_type_url: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GenericAuthorization {
+        #[prost(string, tag = "1")]
+        pub msg: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SendAuthorization {
+        #[prost(message, repeated, tag = "1")]
+        pub spend_limit: Vec<Coin>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct StakeAuthorization {
+        #[prost(message, optional, tag = "1")]
+        pub max_tokens: Option<Coin>,
+        #[prost(int32, tag = "4")]
+        pub authorization_type: i32,
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(MsgExecDecoder);
+    registry.register(MsgGrantDecoder);
+    registry.register(MsgRevokeDecoder);
+}
+
+pub struct MsgExecDecoder;
+
+impl MsgDecoder for MsgExecDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.authz.v1beta1.MsgExec"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = v1beta1::MsgExec::decode(data)?;
+        Ok(vec![address("grantee", &parsed.grantee)])
+    }
+
+    fn children(&self, data: &[u8]) -> cosmrs::Result<Vec<Any>> {
+        Ok(v1beta1::MsgExec::decode(data)?.msgs)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = v1beta1::MsgExec::decode(data)?;
+        Ok(json!({
+            "grantee": parsed.grantee,
+            "msgs": parsed.msgs.iter().map(|msg| msg.type_url.clone()).collect::<Value>(),
+        }))
+    }
+}
+
+/// The message type a grant authorizes, the type URL of the authorization otherwise
+fn authorized_msg_type_url(authorization: &Any) -> cosmrs::Result<String> {
+    Ok(match authorization.type_url.as_str() {
+        "/cosmos.authz.v1beta1.GenericAuthorization" => {
+            v1beta1::GenericAuthorization::decode(&authorization.value[..])?.msg
+        }
+        "/cosmos.bank.v1beta1.SendAuthorization" => "/cosmos.bank.v1beta1.MsgSend".to_string(),
+        "/cosmos.staking.v1beta1.StakeAuthorization" => {
+            let parsed = v1beta1::StakeAuthorization::decode(&authorization.value[..])?;
+            match parsed.authorization_type {
+                1 => "/cosmos.staking.v1beta1.MsgDelegate",
+                2 => "/cosmos.staking.v1beta1.MsgUndelegate",
+                3 => "/cosmos.staking.v1beta1.MsgBeginRedelegate",
+                _ => "",
+            }
+            .to_string()
+        }
+        _ => authorization.type_url.clone(),
+    })
+}
+
+fn grant_to_model(parsed: v1beta1::MsgGrant) -> cosmrs::Result<model::Grant> {
+    let grant = parsed.grant.unwrap_or_default();
+    let authorization = grant.authorization.unwrap_or_default();
+    Ok(model::Grant {
+        msg_type_url: authorized_msg_type_url(&authorization)?,
+        authorization_type: authorization.type_url,
+        expiration: grant
+            .expiration
+            .and_then(|ts| Utc.timestamp_opt(ts.seconds, ts.nanos as u32).single()),
+        granter: parsed.granter,
+        grantee: parsed.grantee,
+    })
+}
+
+pub struct MsgGrantDecoder;
+
+impl MsgDecoder for MsgGrantDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.authz.v1beta1.MsgGrant"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = v1beta1::MsgGrant::decode(data)?;
+        Ok(vec![
+            address("granter", &parsed.granter),
+            address("grantee", &parsed.grantee),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = v1beta1::MsgGrant::decode(data)?;
+        Ok(vec![model::Action::Grant(grant_to_model(parsed)?)])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = v1beta1::MsgGrant::decode(data)?;
+        let spend_limit = parsed
+            .grant
+            .as_ref()
+            .and_then(|grant| grant.authorization.as_ref())
+            .filter(|auth| auth.type_url == "/cosmos.bank.v1beta1.SendAuthorization")
+            .map(|auth| v1beta1::SendAuthorization::decode(&auth.value[..]))
+            .transpose()?
+            .map(|auth| coins_json(&auth.spend_limit));
+        let grant = grant_to_model(parsed)?;
+        Ok(json!({
+            "granter": grant.granter,
+            "grantee": grant.grantee,
+            "authorization_type": grant.authorization_type,
+            "msg_type_url": grant.msg_type_url,
+            "spend_limit": spend_limit,
+            "expiration": grant.expiration.map(|time| time.to_rfc3339()),
+        }))
+    }
+}
+
+pub struct MsgRevokeDecoder;
+
+impl MsgDecoder for MsgRevokeDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.authz.v1beta1.MsgRevoke"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = v1beta1::MsgRevoke::decode(data)?;
+        Ok(vec![
+            address("granter", &parsed.granter),
+            address("grantee", &parsed.grantee),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = v1beta1::MsgRevoke::decode(data)?;
+        Ok(vec![model::Action::Revoke(model::Revoke {
+            granter: parsed.granter,
+            grantee: parsed.grantee,
+            msg_type_url: parsed.msg_type_url,
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = v1beta1::MsgRevoke::decode(data)?;
+        Ok(json!({
+            "granter": parsed.granter,
+            "grantee": parsed.grantee,
+            "msg_type_url": parsed.msg_type_url,
+        }))
+    }
+}
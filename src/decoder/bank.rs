@@ -0,0 +1,113 @@
+use cosmrs::proto::cosmos::bank::v1beta1::{MsgMultiSend, MsgSend};
+use cosmrs::proto::cosmos::base::v1beta1::Coin;
+use prost::Message;
+use serde_json::{json, Value};
+
+use crate::decoder::{address, coins_json, MsgDecoder, Registry};
+use crate::fetch;
+use crate::model;
+
+/// Counterparty of multi-send transfers, inputs and outputs are not paired
+pub const MULTI: &str = "MULTI";
+
+pub fn register(registry: &mut Registry) {
+    registry.register(MsgSendDecoder);
+    registry.register(MsgMultiSendDecoder);
+}
+
+fn push_transfers(
+    transfers: &mut Vec<model::Transfer>,
+    sender: &str,
+    receiver: &str,
+    coins: Vec<Coin>,
+) -> cosmrs::Result<()> {
+    for coin in fetch::coins_to_model(coins)? {
+        transfers.push(model::Transfer {
+            index: transfers.len() as u32,
+            sender: sender.to_string(),
+            receiver: receiver.to_string(),
+            denom: coin.denom,
+            amount: coin.amount,
+        })
+    }
+    Ok(())
+}
+
+pub struct MsgSendDecoder;
+
+impl MsgDecoder for MsgSendDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.bank.v1beta1.MsgSend"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgSend::decode(data)?;
+        Ok(vec![
+            address("sender", &parsed.from_address),
+            address("receiver", &parsed.to_address),
+        ])
+    }
+
+    fn transfers(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Transfer>> {
+        let parsed = MsgSend::decode(data)?;
+        let mut transfers = Vec::<model::Transfer>::new();
+        push_transfers(
+            &mut transfers,
+            &parsed.from_address,
+            &parsed.to_address,
+            parsed.amount,
+        )?;
+        Ok(transfers)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgSend::decode(data)?;
+        Ok(json!({
+            "from_address": parsed.from_address,
+            "to_address": parsed.to_address,
+            "amount": coins_json(&parsed.amount),
+        }))
+    }
+}
+
+pub struct MsgMultiSendDecoder;
+
+impl MsgDecoder for MsgMultiSendDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.bank.v1beta1.MsgMultiSend"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgMultiSend::decode(data)?;
+        let mut addresses = Vec::<model::Address>::new();
+        for i in parsed.inputs {
+            addresses.push(address("sender", &i.address));
+        }
+        for o in parsed.outputs {
+            addresses.push(address("receiver", &o.address));
+        }
+        Ok(addresses)
+    }
+
+    fn transfers(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Transfer>> {
+        let parsed = MsgMultiSend::decode(data)?;
+        let mut transfers = Vec::<model::Transfer>::new();
+        for i in parsed.inputs {
+            push_transfers(&mut transfers, &i.address, MULTI, i.coins)?;
+        }
+        for o in parsed.outputs {
+            push_transfers(&mut transfers, MULTI, &o.address, o.coins)?;
+        }
+        Ok(transfers)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgMultiSend::decode(data)?;
+        let io =
+            |address: &str, coins: &[Coin]| json!({"address": address, "coins": coins_json(coins)});
+        Ok(json!({
+            "inputs": parsed.inputs.iter().map(|i| io(&i.address, &i.coins)).collect::<Value>(),
+            "outputs": parsed.outputs.iter().map(|o| io(&o.address, &o.coins)).collect::<Value>(),
+        }))
+    }
+}
@@ -0,0 +1,134 @@
+use cosmrs::proto::cosmos::distribution::v1beta1::{
+    MsgFundCommunityPool, MsgSetWithdrawAddress, MsgWithdrawDelegatorReward,
+    MsgWithdrawValidatorCommission,
+};
+use prost::Message;
+use serde_json::{json, Value};
+
+use crate::decoder::{address, coins_json, MsgDecoder, Registry};
+use crate::fetch;
+use crate::model;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(MsgWithdrawDelegatorRewardDecoder);
+    registry.register(MsgWithdrawValidatorCommissionDecoder);
+    registry.register(MsgSetWithdrawAddressDecoder);
+    registry.register(MsgFundCommunityPoolDecoder);
+}
+
+pub struct MsgWithdrawDelegatorRewardDecoder;
+
+impl MsgDecoder for MsgWithdrawDelegatorRewardDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgWithdrawDelegatorReward::decode(data)?;
+        Ok(vec![
+            address("delegator", &parsed.delegator_address),
+            address("validator", &parsed.validator_address),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgWithdrawDelegatorReward::decode(data)?;
+        Ok(vec![model::Action::Withdraw(model::Withdrawal {
+            kind: model::WithdrawalKind::Reward,
+            address: parsed.delegator_address,
+            validator: parsed.validator_address,
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgWithdrawDelegatorReward::decode(data)?;
+        Ok(json!({
+            "delegator_address": parsed.delegator_address,
+            "validator_address": parsed.validator_address,
+        }))
+    }
+}
+
+pub struct MsgWithdrawValidatorCommissionDecoder;
+
+impl MsgDecoder for MsgWithdrawValidatorCommissionDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgWithdrawValidatorCommission::decode(data)?;
+        Ok(vec![address("validator", &parsed.validator_address)])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgWithdrawValidatorCommission::decode(data)?;
+        Ok(vec![model::Action::Withdraw(model::Withdrawal {
+            kind: model::WithdrawalKind::Commission,
+            address: parsed.validator_address.clone(),
+            validator: parsed.validator_address,
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgWithdrawValidatorCommission::decode(data)?;
+        Ok(json!({
+            "validator_address": parsed.validator_address,
+        }))
+    }
+}
+
+pub struct MsgSetWithdrawAddressDecoder;
+
+impl MsgDecoder for MsgSetWithdrawAddressDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgSetWithdrawAddress::decode(data)?;
+        Ok(vec![
+            address("delegator", &parsed.delegator_address),
+            address("withdraw", &parsed.withdraw_address),
+        ])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgSetWithdrawAddress::decode(data)?;
+        Ok(json!({
+            "delegator_address": parsed.delegator_address,
+            "withdraw_address": parsed.withdraw_address,
+        }))
+    }
+}
+
+pub struct MsgFundCommunityPoolDecoder;
+
+impl MsgDecoder for MsgFundCommunityPoolDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.distribution.v1beta1.MsgFundCommunityPool"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgFundCommunityPool::decode(data)?;
+        Ok(vec![address("depositor", &parsed.depositor)])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgFundCommunityPool::decode(data)?;
+        Ok(vec![model::Action::FundCommunityPool(
+            model::CommunityPoolFunding {
+                depositor: parsed.depositor,
+                amount: fetch::coins_to_model(parsed.amount)?,
+            },
+        )])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgFundCommunityPool::decode(data)?;
+        Ok(json!({
+            "depositor": parsed.depositor,
+            "amount": coins_json(&parsed.amount),
+        }))
+    }
+}
@@ -0,0 +1,273 @@
+use cosmrs::proto::cosmos::gov::v1beta1;
+use prost::Message;
+use serde_json::{json, Value};
+
+use crate::decoder::{address, dec, model_coins_json, MsgDecoder, Registry};
+use crate::fetch;
+use crate::model;
+
+/// cosmos-sdk-proto only ships gov v1beta1, these mirror cosmos/gov/v1/tx.proto
+pub mod v1 {
+    use cosmrs::proto::cosmos::base::v1beta1::Coin;
+    use cosmrs::Any;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgSubmitProposal {
+        #[prost(message, repeated, tag = "1")]
+        pub messages: Vec<Any>,
+        #[prost(message, repeated, tag = "2")]
+        pub initial_deposit: Vec<Coin>,
+        #[prost(string, tag = "3")]
+        pub proposer: String,
+        #[prost(string, tag = "4")]
+        pub metadata: String,
+        #[prost(string, tag = "5")]
+        pub title: String,
+        #[prost(string, tag = "6")]
+        pub summary: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgDeposit {
+        #[prost(uint64, tag = "1")]
+        pub proposal_id: u64,
+        #[prost(string, tag = "2")]
+        pub depositor: String,
+        #[prost(message, repeated, tag = "3")]
+        pub amount: Vec<Coin>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgVote {
+        #[prost(uint64, tag = "1")]
+        pub proposal_id: u64,
+        #[prost(string, tag = "2")]
+        pub voter: String,
+        #[prost(int32, tag = "3")]
+        pub option: i32,
+        #[prost(string, tag = "4")]
+        pub metadata: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct WeightedVoteOption {
+        #[prost(int32, tag = "1")]
+        pub option: i32,
+        #[prost(string, tag = "2")]
+        pub weight: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgVoteWeighted {
+        #[prost(uint64, tag = "1")]
+        pub proposal_id: u64,
+        #[prost(string, tag = "2")]
+        pub voter: String,
+        #[prost(message, repeated, tag = "3")]
+        pub options: Vec<WeightedVoteOption>,
+        #[prost(string, tag = "4")]
+        pub metadata: String,
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    let decoders: [(&'static str, Decode); 8] = [
+        (
+            "/cosmos.gov.v1beta1.MsgSubmitProposal",
+            v1beta1_submit_proposal,
+        ),
+        ("/cosmos.gov.v1beta1.MsgDeposit", v1beta1_deposit),
+        ("/cosmos.gov.v1beta1.MsgVote", v1beta1_vote),
+        ("/cosmos.gov.v1beta1.MsgVoteWeighted", v1beta1_vote_weighted),
+        ("/cosmos.gov.v1.MsgSubmitProposal", v1_submit_proposal),
+        ("/cosmos.gov.v1.MsgDeposit", v1_deposit),
+        ("/cosmos.gov.v1.MsgVote", v1_vote),
+        ("/cosmos.gov.v1.MsgVoteWeighted", v1_vote_weighted),
+    ];
+
+    for (type_url, decode) in decoders {
+        registry.register(GovDecoder { type_url, decode });
+    }
+}
+
+type Decode = fn(&[u8]) -> cosmrs::Result<model::Action>;
+
+/// Both gov versions are normalized into a model::Action,
+/// addresses and JSON are then derived from it.
+pub struct GovDecoder {
+    type_url: &'static str,
+    decode: Decode,
+}
+
+impl MsgDecoder for GovDecoder {
+    fn type_url(&self) -> &'static str {
+        self.type_url
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        Ok(match (self.decode)(data)? {
+            model::Action::SubmitProposal(p) => vec![address("proposer", &p.proposer)],
+            model::Action::Deposit(d) => vec![address("depositor", &d.depositor)],
+            model::Action::Vote(v) => vec![address("voter", &v.voter)],
+            _ => vec![],
+        })
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        Ok(vec![(self.decode)(data)?])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        Ok(match (self.decode)(data)? {
+            model::Action::SubmitProposal(p) => json!({
+                "proposer": p.proposer,
+                "content_type": p.content_type,
+                "title": p.title,
+                "summary": p.summary,
+                "initial_deposit": model_coins_json(&p.initial_deposit),
+            }),
+            model::Action::Deposit(d) => json!({
+                "proposal_id": d.proposal_id,
+                "depositor": d.depositor,
+                "amount": model_coins_json(&d.amount),
+            }),
+            model::Action::Vote(v) => json!({
+                "proposal_id": v.proposal_id,
+                "voter": v.voter,
+                "options": v.options
+                    .iter()
+                    .map(|o| json!({"option": o.option, "weight": o.weight}))
+                    .collect::<Value>(),
+            }),
+            _ => Value::Null,
+        })
+    }
+}
+
+fn vote_option(option: i32) -> String {
+    match v1beta1::VoteOption::from_i32(option) {
+        Some(v1beta1::VoteOption::Yes) => "yes",
+        Some(v1beta1::VoteOption::Abstain) => "abstain",
+        Some(v1beta1::VoteOption::No) => "no",
+        Some(v1beta1::VoteOption::NoWithVeto) => "no_with_veto",
+        _ => "unspecified",
+    }
+    .to_string()
+}
+
+fn single_vote(option: i32) -> Vec<model::VoteOption> {
+    vec![model::VoteOption {
+        option: vote_option(option),
+        weight: "1".to_string(),
+    }]
+}
+
+fn weighted_vote(options: Vec<(i32, String)>) -> Vec<model::VoteOption> {
+    options
+        .into_iter()
+        .map(|(option, weight)| model::VoteOption {
+            option: vote_option(option),
+            weight,
+        })
+        .collect()
+}
+
+fn v1beta1_submit_proposal(data: &[u8]) -> cosmrs::Result<model::Action> {
+    let parsed = v1beta1::MsgSubmitProposal::decode(data)?;
+    let content = parsed.content.unwrap_or_default();
+    // Every legacy content type starts with title and description, fall back on other layouts
+    let text = v1beta1::TextProposal::decode(&content.value[..]).unwrap_or_default();
+
+    Ok(model::Action::SubmitProposal(model::Proposal {
+        version: "v1beta1".to_string(),
+        proposer: parsed.proposer,
+        content_type: content.type_url,
+        title: text.title,
+        summary: text.description,
+        initial_deposit: fetch::coins_to_model(parsed.initial_deposit)?,
+    }))
+}
+
+fn v1beta1_deposit(data: &[u8]) -> cosmrs::Result<model::Action> {
+    let parsed = v1beta1::MsgDeposit::decode(data)?;
+    Ok(model::Action::Deposit(model::Deposit {
+        proposal_id: parsed.proposal_id,
+        depositor: parsed.depositor,
+        amount: fetch::coins_to_model(parsed.amount)?,
+    }))
+}
+
+fn v1beta1_vote(data: &[u8]) -> cosmrs::Result<model::Action> {
+    let parsed = v1beta1::MsgVote::decode(data)?;
+    Ok(model::Action::Vote(model::Vote {
+        proposal_id: parsed.proposal_id,
+        voter: parsed.voter,
+        options: single_vote(parsed.option),
+    }))
+}
+
+fn v1beta1_vote_weighted(data: &[u8]) -> cosmrs::Result<model::Action> {
+    let parsed = v1beta1::MsgVoteWeighted::decode(data)?;
+    Ok(model::Action::Vote(model::Vote {
+        proposal_id: parsed.proposal_id,
+        voter: parsed.voter,
+        options: weighted_vote(
+            parsed
+                .options
+                .into_iter()
+                .map(|o| Ok((o.option, dec(&o.weight)?)))
+                .collect::<cosmrs::Result<_>>()?,
+        ),
+    }))
+}
+
+fn v1_submit_proposal(data: &[u8]) -> cosmrs::Result<model::Action> {
+    let parsed = v1::MsgSubmitProposal::decode(data)?;
+    Ok(model::Action::SubmitProposal(model::Proposal {
+        version: "v1".to_string(),
+        proposer: parsed.proposer,
+        content_type: parsed
+            .messages
+            .iter()
+            .map(|msg| msg.type_url.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+        title: parsed.title,
+        summary: parsed.summary,
+        initial_deposit: fetch::coins_to_model(parsed.initial_deposit)?,
+    }))
+}
+
+fn v1_deposit(data: &[u8]) -> cosmrs::Result<model::Action> {
+    let parsed = v1::MsgDeposit::decode(data)?;
+    Ok(model::Action::Deposit(model::Deposit {
+        proposal_id: parsed.proposal_id,
+        depositor: parsed.depositor,
+        amount: fetch::coins_to_model(parsed.amount)?,
+    }))
+}
+
+fn v1_vote(data: &[u8]) -> cosmrs::Result<model::Action> {
+    let parsed = v1::MsgVote::decode(data)?;
+    Ok(model::Action::Vote(model::Vote {
+        proposal_id: parsed.proposal_id,
+        voter: parsed.voter,
+        options: single_vote(parsed.option),
+    }))
+}
+
+/// Unlike v1beta1, v1 weights are `cosmos.Dec` scalars sent as plain decimal strings
+fn v1_vote_weighted(data: &[u8]) -> cosmrs::Result<model::Action> {
+    let parsed = v1::MsgVoteWeighted::decode(data)?;
+    Ok(model::Action::Vote(model::Vote {
+        proposal_id: parsed.proposal_id,
+        voter: parsed.voter,
+        options: weighted_vote(
+            parsed
+                .options
+                .into_iter()
+                .map(|o| (o.option, o.weight))
+                .collect(),
+        ),
+    }))
+}
@@ -0,0 +1,249 @@
+use cosmrs::proto::ibc::applications::transfer::v1::MsgTransfer;
+use cosmrs::proto::ibc::core::channel::v1::{
+    MsgAcknowledgement, MsgRecvPacket, MsgTimeout, Packet,
+};
+use prost::Message;
+use serde_json::{json, Value};
+
+use crate::decoder::{address, MsgDecoder, Registry};
+use crate::model;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(MsgTransferDecoder);
+    registry.register(MsgRecvPacketDecoder);
+    registry.register(MsgAcknowledgementDecoder);
+    registry.register(MsgTimeoutDecoder);
+}
+
+/// ICS-20 packet payload, JSON encoded in `Packet.data`
+#[derive(Debug, serde::Deserialize)]
+struct FungibleTokenPacketData {
+    denom: String,
+    amount: String,
+    sender: String,
+    receiver: String,
+}
+
+fn packet_data(packet: &Packet) -> Option<FungibleTokenPacketData> {
+    serde_json::from_slice(&packet.data).ok()
+}
+
+fn packet_to_model(packet: &Packet) -> model::IbcPacket {
+    model::IbcPacket {
+        source_port: packet.source_port.clone(),
+        source_channel: packet.source_channel.clone(),
+        sequence: packet.sequence,
+    }
+}
+
+fn packet_transfer(
+    packet: Packet,
+    data: FungibleTokenPacketData,
+) -> cosmrs::Result<model::IbcTransfer> {
+    Ok(model::IbcTransfer {
+        source_port: packet.source_port,
+        source_channel: packet.source_channel,
+        sequence: Some(packet.sequence),
+        destination_port: Some(packet.destination_port),
+        destination_channel: Some(packet.destination_channel),
+        sender: data.sender,
+        receiver: data.receiver,
+        denom: data.denom,
+        amount: data.amount.parse()?,
+    })
+}
+
+fn packet_json(packet: &Packet) -> Value {
+    let data = packet_data(packet);
+    json!({
+        "sequence": packet.sequence,
+        "source_port": packet.source_port,
+        "source_channel": packet.source_channel,
+        "destination_port": packet.destination_port,
+        "destination_channel": packet.destination_channel,
+        "data": data.map(|d| json!({
+            "denom": d.denom,
+            "amount": d.amount,
+            "sender": d.sender,
+            "receiver": d.receiver,
+        })),
+    })
+}
+
+/// Relayers also deliver non ICS-20 packets, those only yield the relayer address
+fn packet_addresses(signer: &str, packet: &Packet) -> Vec<model::Address> {
+    let mut addresses = vec![address("relayer", signer)];
+    if let Some(data) = packet_data(packet) {
+        addresses.push(address("sender", &data.sender));
+        addresses.push(address("receiver", &data.receiver));
+    }
+    addresses
+}
+
+pub struct MsgTransferDecoder;
+
+impl MsgDecoder for MsgTransferDecoder {
+    fn type_url(&self) -> &'static str {
+        "/ibc.applications.transfer.v1.MsgTransfer"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgTransfer::decode(data)?;
+        Ok(vec![
+            address("sender", &parsed.sender),
+            address("receiver", &parsed.receiver),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgTransfer::decode(data)?;
+        let token = parsed.token.unwrap_or_default();
+        Ok(vec![model::Action::IbcSend(model::IbcTransfer {
+            source_port: parsed.source_port,
+            source_channel: parsed.source_channel,
+            sequence: None,
+            destination_port: None,
+            destination_channel: None,
+            sender: parsed.sender,
+            receiver: parsed.receiver,
+            denom: token.denom,
+            amount: token.amount.parse()?,
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgTransfer::decode(data)?;
+        let token = parsed.token.unwrap_or_default();
+        Ok(json!({
+            "source_port": parsed.source_port,
+            "source_channel": parsed.source_channel,
+            "token": {"denom": token.denom, "amount": token.amount},
+            "sender": parsed.sender,
+            "receiver": parsed.receiver,
+            "timeout_height": parsed.timeout_height.map(|h| json!({
+                "revision_number": h.revision_number,
+                "revision_height": h.revision_height,
+            })),
+            "timeout_timestamp": parsed.timeout_timestamp,
+        }))
+    }
+}
+
+pub struct MsgRecvPacketDecoder;
+
+impl MsgDecoder for MsgRecvPacketDecoder {
+    fn type_url(&self) -> &'static str {
+        "/ibc.core.channel.v1.MsgRecvPacket"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgRecvPacket::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        Ok(packet_addresses(&parsed.signer, &packet))
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgRecvPacket::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        match packet_data(&packet) {
+            Some(data) => Ok(vec![model::Action::IbcReceive(packet_transfer(
+                packet, data,
+            )?)]),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgRecvPacket::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        Ok(json!({
+            "packet": packet_json(&packet),
+            "signer": parsed.signer,
+        }))
+    }
+}
+
+pub struct MsgAcknowledgementDecoder;
+
+impl MsgAcknowledgementDecoder {
+    /// ICS-20 acknowledgements are either `{"result": ...}` or `{"error": ...}`
+    fn is_success(acknowledgement: &[u8]) -> bool {
+        serde_json::from_slice::<Value>(acknowledgement)
+            .map(|ack| ack.get("error").is_none())
+            .unwrap_or(true)
+    }
+}
+
+impl MsgDecoder for MsgAcknowledgementDecoder {
+    fn type_url(&self) -> &'static str {
+        "/ibc.core.channel.v1.MsgAcknowledgement"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgAcknowledgement::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        Ok(packet_addresses(&parsed.signer, &packet))
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgAcknowledgement::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        let success = Self::is_success(&parsed.acknowledgement);
+        let mut actions = vec![model::Action::IbcAcknowledge(
+            packet_to_model(&packet),
+            success,
+        )];
+        // The sender gets the tokens back when the counterparty failed to process the packet
+        match packet_data(&packet) {
+            Some(data) if !success => {
+                actions.push(model::Action::IbcRefund(packet_transfer(packet, data)?))
+            }
+            _ => {}
+        }
+        Ok(actions)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgAcknowledgement::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        Ok(json!({
+            "packet": packet_json(&packet),
+            "acknowledgement": String::from_utf8_lossy(&parsed.acknowledgement),
+            "signer": parsed.signer,
+        }))
+    }
+}
+
+pub struct MsgTimeoutDecoder;
+
+impl MsgDecoder for MsgTimeoutDecoder {
+    fn type_url(&self) -> &'static str {
+        "/ibc.core.channel.v1.MsgTimeout"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgTimeout::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        Ok(packet_addresses(&parsed.signer, &packet))
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgTimeout::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        let mut actions = vec![model::Action::IbcTimeout(packet_to_model(&packet))];
+        if let Some(data) = packet_data(&packet) {
+            actions.push(model::Action::IbcRefund(packet_transfer(packet, data)?))
+        }
+        Ok(actions)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgTimeout::decode(data)?;
+        let packet = parsed.packet.unwrap_or_default();
+        Ok(json!({
+            "packet": packet_json(&packet),
+            "next_sequence_recv": parsed.next_sequence_recv,
+            "signer": parsed.signer,
+        }))
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use cosmrs::proto::cosmos::base::v1beta1::Coin;
+use cosmrs::Any;
+use serde_json::{json, Value};
+
+use crate::model;
+
+pub mod authz;
+pub mod bank;
+pub mod distribution;
+pub mod gov;
+pub mod ibc;
+pub mod staking;
+pub mod wasm;
+
+/// Decodes the protobuf payload of one message type.
+///
+/// Decoders are looked up by type URL in a [`Registry`], which is where
+/// support for chain specific messages can be plugged in.
+pub trait MsgDecoder: Send + Sync {
+    fn type_url(&self) -> &'static str;
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>>;
+
+    fn transfers(&self, _data: &[u8]) -> cosmrs::Result<Vec<model::Transfer>> {
+        Ok(vec![])
+    }
+
+    fn actions(&self, _data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        Ok(vec![])
+    }
+
+    /// Messages wrapped by this one, decoded as messages of their own
+    fn children(&self, _data: &[u8]) -> cosmrs::Result<Vec<Any>> {
+        Ok(vec![])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value>;
+}
+
+pub struct Registry {
+    decoders: HashMap<&'static str, Box<dyn MsgDecoder>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Registry::empty();
+        authz::register(&mut registry);
+        bank::register(&mut registry);
+        staking::register(&mut registry);
+        distribution::register(&mut registry);
+        gov::register(&mut registry);
+        ibc::register(&mut registry);
+        wasm::register(&mut registry);
+        registry
+    }
+}
+
+impl Registry {
+    pub fn empty() -> Self {
+        Registry {
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Registering a type URL twice replaces the previous decoder
+    pub fn register<D: MsgDecoder + 'static>(&mut self, decoder: D) {
+        self.decoders.insert(decoder.type_url(), Box::new(decoder));
+    }
+
+    pub fn get(&self, type_url: &str) -> Option<&dyn MsgDecoder> {
+        self.decoders.get(type_url).map(|it| it.as_ref())
+    }
+
+    pub fn addresses(&self, type_url: &str, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        match self.get(type_url) {
+            Some(decoder) => decoder.addresses(data),
+            None => {
+                log::warn!("Unknown type URL : {}", type_url);
+                Ok(vec![])
+            }
+        }
+    }
+
+    pub fn transfers(&self, type_url: &str, data: &[u8]) -> cosmrs::Result<Vec<model::Transfer>> {
+        match self.get(type_url) {
+            Some(decoder) => decoder.transfers(data),
+            None => Ok(vec![]),
+        }
+    }
+
+    pub fn actions(&self, type_url: &str, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        match self.get(type_url) {
+            Some(decoder) => decoder.actions(data),
+            None => Ok(vec![]),
+        }
+    }
+
+    pub fn children(&self, type_url: &str, data: &[u8]) -> cosmrs::Result<Vec<Any>> {
+        match self.get(type_url) {
+            Some(decoder) => decoder.children(data),
+            None => Ok(vec![]),
+        }
+    }
+
+    pub fn json(&self, type_url: &str, data: &[u8]) -> cosmrs::Result<Option<Value>> {
+        self.get(type_url)
+            .map(|decoder| decoder.json(data))
+            .transpose()
+    }
+}
+
+pub fn address(role: &str, address: &str) -> model::Address {
+    model::Address {
+        role: role.to_string(),
+        address: address.to_string(),
+    }
+}
+
+/// Decimal places of an `sdk.Dec`
+const DEC_PRECISION: usize = 18;
+
+/// Amino `sdk.Dec` fields go on the wire as their 10^18 scaled integer, 0.1 is sent as
+/// `100000000000000000`. Gives back the `0.100000000000000000` form used in genesis files.
+pub fn dec(scaled: &str) -> cosmrs::Result<String> {
+    let (sign, digits) = match scaled.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", scaled),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(cosmrs::ErrorReport::msg(format!(
+            "Invalid Dec : {}",
+            scaled
+        )));
+    }
+
+    let digits = format!("{:0>width$}", digits, width = DEC_PRECISION + 1);
+    let (int, frac) = digits.split_at(digits.len() - DEC_PRECISION);
+    let int = match int.trim_start_matches('0') {
+        "" => "0",
+        int => int,
+    };
+    Ok(format!("{}{}.{}", sign, int, frac))
+}
+
+pub fn model_coins_json(coins: &[model::Coin]) -> Value {
+    coins
+        .iter()
+        .map(|coin| json!({"denom": coin.denom, "amount": coin.amount.to_string()}))
+        .collect()
+}
+
+pub fn coins_json(coins: &[Coin]) -> Value {
+    coins
+        .iter()
+        .map(|coin| json!({"denom": coin.denom, "amount": coin.amount}))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dec_scales_down_the_wire_value() {
+        assert_eq!(dec("700000000000000000").unwrap(), "0.700000000000000000");
+        assert_eq!(dec("1000000000000000000").unwrap(), "1.000000000000000000");
+        assert_eq!(
+            dec("25000000000000000000").unwrap(),
+            "25.000000000000000000"
+        );
+        assert_eq!(dec("5").unwrap(), "0.000000000000000005");
+        assert_eq!(dec("0").unwrap(), "0.000000000000000000");
+        assert_eq!(dec("-1").unwrap(), "-0.000000000000000001");
+
+        assert!(dec("").is_err());
+        assert!(dec("0.7").is_err());
+        assert!(dec("1e18").is_err());
+    }
+}
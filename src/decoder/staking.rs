@@ -0,0 +1,399 @@
+use cosmrs::proto::cosmos::base::v1beta1::Coin;
+use cosmrs::proto::cosmos::crypto::ed25519;
+use cosmrs::proto::cosmos::staking::v1beta1::{
+    Description, MsgBeginRedelegate, MsgCreateValidator, MsgDelegate, MsgEditValidator,
+    MsgUndelegate,
+};
+use cosmrs::Any;
+use prost::Message;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::decoder::{address, coins_json, dec, MsgDecoder, Registry};
+use crate::fetch;
+use crate::model;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(MsgDelegateDecoder);
+    registry.register(MsgUndelegateDecoder);
+    registry.register(MsgBeginRedelegateDecoder);
+    registry.register(MsgCreateValidatorDecoder);
+    registry.register(MsgEditValidatorDecoder);
+}
+
+fn delegation_change(
+    delegator: &str,
+    validator: &str,
+    amount: Option<Coin>,
+    sign: i128,
+) -> cosmrs::Result<Vec<model::Action>> {
+    let mut actions = Vec::new();
+    for coin in fetch::coins_to_model(amount.into_iter().collect())? {
+        actions.push(model::Action::DelegationChange(model::DelegationChange {
+            delegator: delegator.to_string(),
+            validator: validator.to_string(),
+            denom: coin.denom,
+            amount: sign * i128::try_from(coin.amount)?,
+        }))
+    }
+    Ok(actions)
+}
+
+pub struct MsgDelegateDecoder;
+
+impl MsgDecoder for MsgDelegateDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.staking.v1beta1.MsgDelegate"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgDelegate::decode(data)?;
+        Ok(vec![
+            address("delegator", &parsed.delegator_address),
+            address("validator", &parsed.validator_address),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgDelegate::decode(data)?;
+        let (delegator, validator) = (&parsed.delegator_address, &parsed.validator_address);
+        delegation_change(delegator, validator, parsed.amount, 1)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgDelegate::decode(data)?;
+        Ok(json!({
+            "delegator_address": parsed.delegator_address,
+            "validator_address": parsed.validator_address,
+            "amount": coins_json(parsed.amount.as_slice()),
+        }))
+    }
+}
+
+pub struct MsgUndelegateDecoder;
+
+impl MsgDecoder for MsgUndelegateDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.staking.v1beta1.MsgUndelegate"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgUndelegate::decode(data)?;
+        Ok(vec![
+            address("delegator", &parsed.delegator_address),
+            address("validator", &parsed.validator_address),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgUndelegate::decode(data)?;
+        let (delegator, validator) = (&parsed.delegator_address, &parsed.validator_address);
+        delegation_change(delegator, validator, parsed.amount, -1)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgUndelegate::decode(data)?;
+        Ok(json!({
+            "delegator_address": parsed.delegator_address,
+            "validator_address": parsed.validator_address,
+            "amount": coins_json(parsed.amount.as_slice()),
+        }))
+    }
+}
+
+pub struct MsgBeginRedelegateDecoder;
+
+impl MsgDecoder for MsgBeginRedelegateDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.staking.v1beta1.MsgBeginRedelegate"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgBeginRedelegate::decode(data)?;
+        Ok(vec![
+            address("delegator", &parsed.delegator_address),
+            address("validator_src", &parsed.validator_src_address),
+            address("validator_dst", &parsed.validator_dst_address),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgBeginRedelegate::decode(data)?;
+        let delegator = &parsed.delegator_address;
+        let (src, dst) = (&parsed.validator_src_address, &parsed.validator_dst_address);
+        let mut actions = delegation_change(delegator, src, parsed.amount.clone(), -1)?;
+        actions.extend(delegation_change(delegator, dst, parsed.amount, 1)?);
+        Ok(actions)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgBeginRedelegate::decode(data)?;
+        Ok(json!({
+            "delegator_address": parsed.delegator_address,
+            "validator_src_address": parsed.validator_src_address,
+            "validator_dst_address": parsed.validator_dst_address,
+            "amount": coins_json(parsed.amount.as_slice()),
+        }))
+    }
+}
+
+/// Placeholder sent by MsgEditValidator for description fields left untouched
+const DO_NOT_MODIFY: &str = "[do-not-modify]";
+
+fn modified(value: String) -> Option<String> {
+    if value == DO_NOT_MODIFY {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Address Tendermint reports as block proposer for an ed25519 consensus key
+pub fn ed25519_address(key: &[u8]) -> String {
+    hex::encode_upper(&Sha256::digest(key)[..20])
+}
+
+/// Consensus key as hex, along with the address Tendermint reports as block proposer
+fn consensus_key(pubkey: &Any) -> cosmrs::Result<(String, Option<String>)> {
+    match pubkey.type_url.as_str() {
+        "/cosmos.crypto.ed25519.PubKey" => {
+            let key = ed25519::PubKey::decode(&pubkey.value[..])?.key;
+            let address = ed25519_address(&key);
+            Ok((hex::encode(key), Some(address)))
+        }
+        _ => Ok((hex::encode(&pubkey.value), None)),
+    }
+}
+
+fn description_json(description: &Option<Description>) -> Value {
+    description
+        .as_ref()
+        .map(|d| {
+            json!({
+                "moniker": d.moniker,
+                "identity": d.identity,
+                "website": d.website,
+                "security_contact": d.security_contact,
+                "details": d.details,
+            })
+        })
+        .unwrap_or(Value::Null)
+}
+
+pub struct MsgCreateValidatorDecoder;
+
+impl MsgDecoder for MsgCreateValidatorDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.staking.v1beta1.MsgCreateValidator"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgCreateValidator::decode(data)?;
+        Ok(vec![
+            address("delegator", &parsed.delegator_address),
+            address("validator", &parsed.validator_address),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgCreateValidator::decode(data)?;
+        let (consensus_pubkey, consensus_address) = match &parsed.pubkey {
+            Some(pubkey) => {
+                let (key, address) = consensus_key(pubkey)?;
+                (Some(key), address)
+            }
+            None => (None, None),
+        };
+        let description = parsed.description.unwrap_or_default();
+        let commission = parsed.commission.unwrap_or_default();
+        // The self delegation comes along with the validator
+        let (delegator, validator) = (&parsed.delegator_address, &parsed.validator_address);
+        let self_delegation = delegation_change(delegator, validator, parsed.value, 1)?;
+
+        let mut actions = vec![model::Action::CreateValidator(model::Validator {
+            operator_address: parsed.validator_address,
+            consensus_pubkey,
+            consensus_address,
+            moniker: Some(description.moniker),
+            identity: Some(description.identity),
+            website: Some(description.website),
+            security_contact: Some(description.security_contact),
+            details: Some(description.details),
+            commission_rate: Some(dec(&commission.rate)?),
+            commission_max_rate: Some(dec(&commission.max_rate)?),
+            commission_max_change_rate: Some(dec(&commission.max_change_rate)?),
+            min_self_delegation: Some(parsed.min_self_delegation),
+        })];
+        actions.extend(self_delegation);
+        Ok(actions)
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgCreateValidator::decode(data)?;
+        Ok(json!({
+            "description": description_json(&parsed.description),
+            "commission": parsed.commission.map(|c| -> cosmrs::Result<Value> {
+                Ok(json!({
+                    "rate": dec(&c.rate)?,
+                    "max_rate": dec(&c.max_rate)?,
+                    "max_change_rate": dec(&c.max_change_rate)?,
+                }))
+            }).transpose()?,
+            "min_self_delegation": parsed.min_self_delegation,
+            "delegator_address": parsed.delegator_address,
+            "validator_address": parsed.validator_address,
+            "pubkey": parsed.pubkey.as_ref().map(consensus_key).transpose()?.map(|it| it.0),
+            "value": coins_json(parsed.value.as_slice()),
+        }))
+    }
+}
+
+pub struct MsgEditValidatorDecoder;
+
+impl MsgDecoder for MsgEditValidatorDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmos.staking.v1beta1.MsgEditValidator"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgEditValidator::decode(data)?;
+        Ok(vec![address("validator", &parsed.validator_address)])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgEditValidator::decode(data)?;
+        let description = parsed.description.unwrap_or_else(|| Description {
+            moniker: DO_NOT_MODIFY.to_string(),
+            identity: DO_NOT_MODIFY.to_string(),
+            website: DO_NOT_MODIFY.to_string(),
+            security_contact: DO_NOT_MODIFY.to_string(),
+            details: DO_NOT_MODIFY.to_string(),
+        });
+
+        Ok(vec![model::Action::EditValidator(model::Validator {
+            operator_address: parsed.validator_address,
+            consensus_pubkey: None,
+            consensus_address: None,
+            moniker: modified(description.moniker),
+            identity: modified(description.identity),
+            website: modified(description.website),
+            security_contact: modified(description.security_contact),
+            details: modified(description.details),
+            commission_rate: non_empty(parsed.commission_rate)
+                .map(|rate| dec(&rate))
+                .transpose()?,
+            commission_max_rate: None,
+            commission_max_change_rate: None,
+            min_self_delegation: non_empty(parsed.min_self_delegation),
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgEditValidator::decode(data)?;
+        Ok(json!({
+            "description": description_json(&parsed.description),
+            "validator_address": parsed.validator_address,
+            "commission_rate": non_empty(parsed.commission_rate).map(|rate| dec(&rate)).transpose()?,
+            "min_self_delegation": parsed.min_self_delegation,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmrs::proto::cosmos::staking::v1beta1::CommissionRates;
+
+    const VALIDATOR: &str = "cosmosvaloper1sxmr0k8u6trd5c6eu6trzyapzux7090ycxtlss";
+
+    fn validator(action: &model::Action) -> &model::Validator {
+        match action {
+            model::Action::CreateValidator(validator) => validator,
+            model::Action::EditValidator(validator) => validator,
+            other => panic!("Not a validator : {:?}", other),
+        }
+    }
+
+    #[test]
+    fn create_validator_rates_are_decimals() {
+        let key = ed25519::PubKey { key: vec![7; 32] };
+        let msg = MsgCreateValidator {
+            description: Some(Description {
+                moniker: "a".to_string(),
+                ..Default::default()
+            }),
+            commission: Some(CommissionRates {
+                rate: "100000000000000000".to_string(),
+                max_rate: "200000000000000000".to_string(),
+                max_change_rate: "10000000000000000".to_string(),
+            }),
+            min_self_delegation: "1".to_string(),
+            delegator_address: "cosmos1sxmr0k8u6trd5c6eu6trzyapzux7090y3u5dan".to_string(),
+            validator_address: VALIDATOR.to_string(),
+            pubkey: Some(Any {
+                type_url: "/cosmos.crypto.ed25519.PubKey".to_string(),
+                value: key.encode_to_vec(),
+            }),
+            value: Some(Coin {
+                denom: "uatom".to_string(),
+                amount: "1000".to_string(),
+            }),
+        };
+
+        let actions = MsgCreateValidatorDecoder
+            .actions(&msg.encode_to_vec())
+            .unwrap();
+        assert_eq!(actions.len(), 2);
+        let created = validator(&actions[0]);
+        assert_eq!(created.operator_address, VALIDATOR);
+        assert_eq!(created.moniker.as_deref(), Some("a"));
+        assert_eq!(created.consensus_address, Some(ed25519_address(&[7; 32])));
+        assert_eq!(
+            created.commission_rate.as_deref(),
+            Some("0.100000000000000000")
+        );
+        assert_eq!(
+            created.commission_max_rate.as_deref(),
+            Some("0.200000000000000000")
+        );
+        assert_eq!(
+            created.commission_max_change_rate.as_deref(),
+            Some("0.010000000000000000")
+        );
+        assert_eq!(created.min_self_delegation.as_deref(), Some("1"));
+        assert!(matches!(
+            &actions[1],
+            model::Action::DelegationChange(change) if change.amount == 1000
+        ));
+    }
+
+    #[test]
+    fn edit_validator_rate_is_a_decimal() {
+        let edit = |commission_rate: &str| {
+            let msg = MsgEditValidator {
+                description: None,
+                validator_address: VALIDATOR.to_string(),
+                commission_rate: commission_rate.to_string(),
+                min_self_delegation: String::new(),
+            };
+            let actions = MsgEditValidatorDecoder
+                .actions(&msg.encode_to_vec())
+                .unwrap();
+            validator(&actions[0]).commission_rate.clone()
+        };
+
+        assert_eq!(
+            edit("150000000000000000").as_deref(),
+            Some("0.150000000000000000")
+        );
+        assert_eq!(edit(""), None);
+    }
+}
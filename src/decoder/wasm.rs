@@ -0,0 +1,266 @@
+use cosmrs::proto::cosmwasm::wasm::v1::{
+    MsgClearAdmin, MsgExecuteContract, MsgInstantiateContract, MsgMigrateContract, MsgStoreCode,
+    MsgUpdateAdmin,
+};
+use prost::Message;
+use serde_json::{json, Value};
+
+use crate::decoder::{address, coins_json, MsgDecoder, Registry};
+use crate::fetch;
+use crate::model;
+
+/// Receiver of instantiate funds, the contract address is only known once executed
+pub const INSTANTIATED: &str = "INSTANTIATED";
+
+pub const INSTANTIATE_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgInstantiateContract";
+
+pub fn register(registry: &mut Registry) {
+    registry.register(MsgStoreCodeDecoder);
+    registry.register(MsgInstantiateContractDecoder);
+    registry.register(MsgExecuteContractDecoder);
+    registry.register(MsgMigrateContractDecoder);
+    registry.register(MsgUpdateAdminDecoder);
+    registry.register(MsgClearAdminDecoder);
+}
+
+/// Contract messages are JSON, kept as text so they stay queryable even when malformed
+fn payload(msg: &[u8]) -> String {
+    String::from_utf8_lossy(msg).into_owned()
+}
+
+fn payload_json(msg: &[u8]) -> Value {
+    serde_json::from_slice(msg).unwrap_or_else(|_| Value::String(payload(msg)))
+}
+
+pub struct MsgStoreCodeDecoder;
+
+impl MsgDecoder for MsgStoreCodeDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmwasm.wasm.v1.MsgStoreCode"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgStoreCode::decode(data)?;
+        Ok(vec![address("sender", &parsed.sender)])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgStoreCode::decode(data)?;
+        // The byte code itself is too large to be of any use here
+        Ok(json!({
+            "sender": parsed.sender,
+            "wasm_byte_code_size": parsed.wasm_byte_code.len(),
+        }))
+    }
+}
+
+pub struct MsgInstantiateContractDecoder;
+
+impl MsgDecoder for MsgInstantiateContractDecoder {
+    fn type_url(&self) -> &'static str {
+        INSTANTIATE_TYPE_URL
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgInstantiateContract::decode(data)?;
+        let mut addresses = vec![address("sender", &parsed.sender)];
+        if !parsed.admin.is_empty() {
+            addresses.push(address("admin", &parsed.admin));
+        }
+        Ok(addresses)
+    }
+
+    fn transfers(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Transfer>> {
+        let parsed = MsgInstantiateContract::decode(data)?;
+        Ok(fetch::coins_to_model(parsed.funds)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, coin)| model::Transfer {
+                index: i as u32,
+                sender: parsed.sender.clone(),
+                receiver: INSTANTIATED.to_string(),
+                denom: coin.denom,
+                amount: coin.amount,
+            })
+            .collect())
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgInstantiateContract::decode(data)?;
+        Ok(vec![model::Action::Instantiate(model::Instantiate {
+            payload: payload(&parsed.msg),
+            sender: parsed.sender,
+            admin: Some(parsed.admin).filter(|admin| !admin.is_empty()),
+            code_id: parsed.code_id,
+            label: parsed.label,
+            funds: fetch::coins_to_model(parsed.funds)?,
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgInstantiateContract::decode(data)?;
+        Ok(json!({
+            "sender": parsed.sender,
+            "admin": parsed.admin,
+            "code_id": parsed.code_id,
+            "label": parsed.label,
+            "msg": payload_json(&parsed.msg),
+            "funds": coins_json(&parsed.funds),
+        }))
+    }
+}
+
+pub struct MsgExecuteContractDecoder;
+
+impl MsgDecoder for MsgExecuteContractDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmwasm.wasm.v1.MsgExecuteContract"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgExecuteContract::decode(data)?;
+        Ok(vec![
+            address("sender", &parsed.sender),
+            address("contract", &parsed.contract),
+        ])
+    }
+
+    fn transfers(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Transfer>> {
+        let parsed = MsgExecuteContract::decode(data)?;
+        Ok(fetch::coins_to_model(parsed.funds)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, coin)| model::Transfer {
+                index: i as u32,
+                sender: parsed.sender.clone(),
+                receiver: parsed.contract.clone(),
+                denom: coin.denom,
+                amount: coin.amount,
+            })
+            .collect())
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgExecuteContract::decode(data)?;
+        Ok(vec![model::Action::Execute(model::Execute {
+            payload: payload(&parsed.msg),
+            sender: parsed.sender,
+            contract: parsed.contract,
+            funds: fetch::coins_to_model(parsed.funds)?,
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgExecuteContract::decode(data)?;
+        Ok(json!({
+            "sender": parsed.sender,
+            "contract": parsed.contract,
+            "msg": payload_json(&parsed.msg),
+            "funds": coins_json(&parsed.funds),
+        }))
+    }
+}
+
+pub struct MsgMigrateContractDecoder;
+
+impl MsgDecoder for MsgMigrateContractDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmwasm.wasm.v1.MsgMigrateContract"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgMigrateContract::decode(data)?;
+        Ok(vec![
+            address("sender", &parsed.sender),
+            address("contract", &parsed.contract),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgMigrateContract::decode(data)?;
+        Ok(vec![model::Action::Migrate(model::Migrate {
+            payload: payload(&parsed.msg),
+            sender: parsed.sender,
+            contract: parsed.contract,
+            code_id: parsed.code_id,
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgMigrateContract::decode(data)?;
+        Ok(json!({
+            "sender": parsed.sender,
+            "contract": parsed.contract,
+            "code_id": parsed.code_id,
+            "msg": payload_json(&parsed.msg),
+        }))
+    }
+}
+
+pub struct MsgUpdateAdminDecoder;
+
+impl MsgDecoder for MsgUpdateAdminDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmwasm.wasm.v1.MsgUpdateAdmin"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgUpdateAdmin::decode(data)?;
+        Ok(vec![
+            address("sender", &parsed.sender),
+            address("contract", &parsed.contract),
+            address("new_admin", &parsed.new_admin),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgUpdateAdmin::decode(data)?;
+        Ok(vec![model::Action::UpdateAdmin(model::UpdateAdmin {
+            sender: parsed.sender,
+            contract: parsed.contract,
+            admin: Some(parsed.new_admin),
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgUpdateAdmin::decode(data)?;
+        Ok(json!({
+            "sender": parsed.sender,
+            "new_admin": parsed.new_admin,
+            "contract": parsed.contract,
+        }))
+    }
+}
+
+pub struct MsgClearAdminDecoder;
+
+impl MsgDecoder for MsgClearAdminDecoder {
+    fn type_url(&self) -> &'static str {
+        "/cosmwasm.wasm.v1.MsgClearAdmin"
+    }
+
+    fn addresses(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Address>> {
+        let parsed = MsgClearAdmin::decode(data)?;
+        Ok(vec![
+            address("sender", &parsed.sender),
+            address("contract", &parsed.contract),
+        ])
+    }
+
+    fn actions(&self, data: &[u8]) -> cosmrs::Result<Vec<model::Action>> {
+        let parsed = MsgClearAdmin::decode(data)?;
+        Ok(vec![model::Action::UpdateAdmin(model::UpdateAdmin {
+            sender: parsed.sender,
+            contract: parsed.contract,
+            admin: None,
+        })])
+    }
+
+    fn json(&self, data: &[u8]) -> cosmrs::Result<Value> {
+        let parsed = MsgClearAdmin::decode(data)?;
+        Ok(json!({
+            "sender": parsed.sender,
+            "contract": parsed.contract,
+        }))
+    }
+}
@@ -1,11 +1,14 @@
+use crate::decoder::Registry;
 use crate::model;
 use crate::tables::msg::MsgRow;
 use chrono::{DateTime, Utc};
 use cosmrs::proto::*;
-use cosmrs::tx::Msg;
-use cosmrs::{rpc, tendermint::abci::Transaction, Any};
+use cosmrs::tendermint::abci::{self, responses::DeliverTx, Transaction};
+use cosmrs::tendermint::block::{Commit, CommitSig};
+use cosmrs::{rpc, Any};
 use prost::Message;
 use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
 
 fn block_time(ts: cosmrs::tendermint::Time) -> DateTime<Utc> {
     // Unwrap is Ok here because the outputed string is well-formed
@@ -20,161 +23,281 @@ fn tx_hash(tx: &Transaction) -> String {
     hex::encode_upper(digest.finalize())
 }
 
-const MULTI: &'static str = "MULTI";
+fn coin_to_model(coin: cosmos::base::v1beta1::Coin) -> cosmrs::Result<model::Coin> {
+    Ok(model::Coin {
+        amount: coin.amount.parse()?,
+        denom: coin.denom,
+    })
+}
 
-fn amount_to_u64(coins: Vec<cosmrs::Coin>) -> u64 {
-    coins[0].amount.to_string().parse().unwrap()
+pub fn coins_to_model(coins: Vec<cosmos::base::v1beta1::Coin>) -> cosmrs::Result<Vec<model::Coin>> {
+    coins.into_iter().map(coin_to_model).collect()
 }
 
-fn amount_to_u64_proto(coins: Vec<cosmos::base::v1beta1::Coin>) -> u64 {
-    coins[0].amount.to_string().parse().unwrap()
+/// Parses the `100uatom,5ibc/27394FB...` amounts found in event attributes
+pub fn parse_coins(coins: &str) -> cosmrs::Result<Vec<model::Coin>> {
+    coins
+        .split(',')
+        .map(str::trim)
+        .filter(|coin| !coin.is_empty())
+        .map(|coin| {
+            let split = coin
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(|| cosmrs::ErrorReport::msg(format!("Missing denom : {}", coin)))?;
+            Ok(model::Coin {
+                amount: coin[..split].parse()?,
+                denom: coin[split..].to_string(),
+            })
+        })
+        .collect()
 }
 
-pub fn msg_transfers(row: MsgRow) -> cosmrs::Result<Vec<model::Transfer>> {
-    let any = Any {
-        type_url: row.tag.clone(),
-        value: row.data,
-    };
+pub fn msg_transfers(registry: &Registry, row: &MsgRow) -> cosmrs::Result<Vec<model::Transfer>> {
+    registry.transfers(&row.tag, &row.data)
+}
 
-    match row.tag.as_str() {
-        "/cosmos.bank.v1beta1.MsgSend" => {
-            let parsed = cosmrs::bank::MsgSend::from_any(&any)?;
-            Ok(vec![model::Transfer {
-                index: row.idx,
-                sender: parsed.from_address.to_string(),
-                receiver: parsed.to_address.to_string(),
-                amount: amount_to_u64(parsed.amount),
-            }])
-        }
-        "/cosmos.bank.v1beta1.MsgMultiSend" => {
-            let parsed = cosmos::bank::v1beta1::MsgMultiSend::decode(&any.value[..])?;
-            let mut transfers = Vec::<model::Transfer>::new();
-
-            for i in parsed.inputs {
-                transfers.push(model::Transfer {
-                    index: row.idx,
-                    sender: i.address,
-                    receiver: MULTI.to_string(),
-                    amount: amount_to_u64_proto(i.coins),
-                })
-            }
-
-            for o in parsed.outputs {
-                transfers.push(model::Transfer {
-                    index: row.idx,
-                    sender: MULTI.to_string(),
-                    receiver: o.address,
-                    amount: amount_to_u64_proto(o.coins),
-                })
-            }
-
-            Ok(transfers)
-        }
-        _ => Ok(vec![]),
+/// A message that cannot be decoded is still stored, without addresses, rather than failing
+/// the block, as indexers skip it when they cannot decode it either
+fn msg_to_model(
+    registry: &Registry,
+    hash: &str,
+    index: u32,
+    parent: Option<u32>,
+    msg: &Any,
+) -> model::Msg {
+    let addresses = registry
+        .addresses(&msg.type_url, &msg.value)
+        .unwrap_or_else(|err| {
+            log::warn!(
+                "Storing message {}/{} ({}) without addresses : {}",
+                hash,
+                index,
+                msg.type_url,
+                err
+            );
+            vec![]
+        });
+    model::Msg {
+        index,
+        parent,
+        tag: msg.type_url.clone(),
+        data: msg.value.clone(),
+        addresses,
     }
 }
 
-fn msg_addresses(msg: &Any) -> cosmrs::Result<Vec<String>> {
-    match msg.type_url.as_str() {
-        "/cosmos.bank.v1beta1.MsgSend" => {
-            let parsed = cosmrs::bank::MsgSend::from_any(msg)?;
-            Ok(vec![
-                parsed.from_address.to_string(),
-                parsed.to_address.to_string(),
-            ])
-        }
-        // TODO pr to cosmrs
-        "/cosmos.bank.v1beta1.MsgMultiSend" => {
-            let parsed = cosmos::bank::v1beta1::MsgMultiSend::decode(&msg.value[..])?;
-            let mut addresses = Vec::<String>::new();
-            for i in parsed.inputs {
-                addresses.push(i.address);
-            }
-            for o in parsed.outputs {
-                addresses.push(o.address);
-            }
-
-            Ok(addresses)
-        }
-        "/cosmos.staking.v1beta1.MsgDelegate" => {
-            let parsed = cosmrs::staking::MsgDelegate::from_any(msg)?;
-            Ok(vec![
-                parsed.delegator_address.to_string(),
-                parsed.validator_address.to_string(),
-            ])
-        }
-        "/cosmos.staking.v1beta1.MsgUndelegate" => {
-            let parsed = cosmrs::staking::MsgUndelegate::from_any(msg)?;
-            Ok(vec![
-                parsed.delegator_address.to_string(),
-                parsed.validator_address.to_string(),
-            ])
-        }
-        "/cosmos.staking.v1beta1.MsgBeginRedelegate" => {
-            let parsed = cosmrs::staking::MsgBeginRedelegate::from_any(msg)?;
-            Ok(vec![
-                parsed.delegator_address.to_string(),
-                parsed.validator_src_address.to_string(),
-                parsed.validator_dst_address.to_string(),
-            ])
-        }
-        "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward" => {
-            let parsed = cosmrs::distribution::MsgWithdrawDelegatorReward::from_any(msg)?;
-            Ok(vec![
-                parsed.delegator_address.to_string(),
-                parsed.validator_address.to_string(),
-            ])
-        }
-        "/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission" => {
-            let parsed = cosmrs::distribution::MsgWithdrawValidatorCommission::from_any(msg)?;
-            Ok(vec![parsed.validator_address.to_string()])
-        }
-        "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress" => {
-            let parsed = cosmrs::distribution::MsgSetWithdrawAddress::from_any(msg)?;
-            Ok(vec![
-                parsed.delegator_address.to_string(),
-                parsed.withdraw_address.to_string(),
-            ])
-        }
-        "/cosmos.distribution.v1beta1.MsgFundCommunityPool" => {
-            let parsed = cosmrs::distribution::MsgFundCommunityPool::from_any(msg)?;
-            Ok(vec![parsed.depositor.to_string()])
+fn event_to_model(index: u32, event: &abci::Event) -> model::Event {
+    model::Event {
+        index,
+        kind: event.type_str.clone(),
+        attributes: event
+            .attributes
+            .iter()
+            .enumerate()
+            .map(|(i, tag)| model::Attribute {
+                index: i as u32,
+                key: tag.key.to_string(),
+                value: tag.value.to_string(),
+            })
+            .collect(),
+    }
+}
+
+pub fn events_to_model(events: &[abci::Event]) -> Vec<model::Event> {
+    events
+        .iter()
+        .enumerate()
+        .map(|(i, event)| event_to_model(i as u32, event))
+        .collect()
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+fn public_key_to_hex(key: &Any) -> cosmrs::Result<String> {
+    match key.type_url.as_str() {
+        "/cosmos.crypto.secp256k1.PubKey" => {
+            let parsed = cosmos::crypto::secp256k1::PubKey::decode(&key.value[..])?;
+            Ok(hex::encode(parsed.key))
         }
-        tag => {
-            log::warn!("Unknown type URL : {}", tag);
-            Ok(vec![])
+        "/cosmos.crypto.ed25519.PubKey" => {
+            let parsed = cosmos::crypto::ed25519::PubKey::decode(&key.value[..])?;
+            Ok(hex::encode(parsed.key))
         }
+        // Multisig and chain specific keys are kept as their raw proto encoding
+        _ => Ok(hex::encode(&key.value)),
     }
 }
 
-fn msg_to_model(index: u32, msg: &Any) -> cosmrs::Result<model::Msg> {
-    Ok(model::Msg {
-        index: index,
-        tag: msg.type_url.clone(),
-        data: msg.value.clone(),
-        addresses: msg_addresses(msg)?,
+fn signer_to_model(
+    index: u32,
+    signer: cosmos::tx::v1beta1::SignerInfo,
+) -> cosmrs::Result<model::Signer> {
+    Ok(model::Signer {
+        index,
+        public_key_type: signer.public_key.as_ref().map(|key| key.type_url.clone()),
+        public_key: signer
+            .public_key
+            .as_ref()
+            .map(public_key_to_hex)
+            .transpose()?,
+        sequence: signer.sequence,
     })
 }
 
-fn tx_to_model(index: u32, tx: &Transaction) -> cosmrs::Result<model::Tx> {
-    let parsed = cosmrs::Tx::from_bytes(tx.as_bytes())?;
+fn tx_to_model(
+    registry: &Registry,
+    index: u32,
+    tx: &Transaction,
+    result: &DeliverTx,
+) -> cosmrs::Result<model::Tx> {
+    // Raw protos rather than cosmrs::Tx, fees may overflow u64 and keys may be of any type
+    let parsed = cosmos::tx::v1beta1::Tx::decode(tx.as_bytes())?;
+    let body = parsed
+        .body
+        .ok_or_else(|| cosmrs::ErrorReport::msg("Missing tx body"))?;
+    let auth_info = parsed
+        .auth_info
+        .ok_or_else(|| cosmrs::ErrorReport::msg("Missing tx auth info"))?;
+    let fee = auth_info.fee.unwrap_or_default();
 
+    // Breadth first so that top level messages keep their index in the tx,
+    // wrapped messages (authz MsgExec) are numbered after them
+    let hash = tx_hash(tx);
     let mut msgs = Vec::<model::Msg>::new();
-    for (i, msg) in parsed.body.messages.iter().enumerate() {
-        msgs.push(msg_to_model(i as u32, msg)?)
+    let mut pending: VecDeque<(Option<u32>, Any)> =
+        body.messages.into_iter().map(|msg| (None, msg)).collect();
+    while let Some((parent, msg)) = pending.pop_front() {
+        let index = msgs.len() as u32;
+        let children = registry
+            .children(&msg.type_url, &msg.value)
+            .unwrap_or_else(|err| {
+                log::warn!(
+                    "Storing message {}/{} ({}) without its wrapped messages : {}",
+                    hash,
+                    index,
+                    msg.type_url,
+                    err
+                );
+                vec![]
+            });
+        for child in children {
+            pending.push_back((Some(index), child));
+        }
+        msgs.push(msg_to_model(registry, &hash, index, parent, &msg))
+    }
+
+    let mut signers = Vec::<model::Signer>::new();
+    for (i, signer) in auth_info.signer_infos.into_iter().enumerate() {
+        signers.push(signer_to_model(i as u32, signer)?)
     }
 
     Ok(model::Tx {
-        index: index,
-        hash: tx_hash(tx),
-        msgs: msgs,
+        index,
+        hash,
+        code: result.code.value(),
+        codespace: result.codespace.to_string(),
+        gas_wanted: result.gas_wanted.value(),
+        gas_used: result.gas_used.value(),
+        log: result.log.to_string(),
+        fee: coins_to_model(fee.amount)?,
+        gas_limit: fee.gas_limit,
+        payer: non_empty(fee.payer),
+        granter: non_empty(fee.granter),
+        memo: body.memo,
+        timeout_height: body.timeout_height,
+        signers,
+        msgs,
+        events: events_to_model(&result.events),
     })
 }
 
-pub fn block_to_model(resp: &rpc::endpoint::block::Response) -> cosmrs::Result<model::Block> {
+fn commit_sig_to_model(index: u32, sig: &CommitSig) -> model::CommitSignature {
+    let (flag, validator_address, timestamp) = match sig {
+        CommitSig::BlockIdFlagAbsent => (model::CommitFlag::Absent, None, None),
+        CommitSig::BlockIdFlagCommit {
+            validator_address,
+            timestamp,
+            ..
+        } => (
+            model::CommitFlag::Commit,
+            Some(validator_address.to_string()),
+            Some(block_time(*timestamp)),
+        ),
+        CommitSig::BlockIdFlagNil {
+            validator_address,
+            timestamp,
+            ..
+        } => (
+            model::CommitFlag::Nil,
+            Some(validator_address.to_string()),
+            Some(block_time(*timestamp)),
+        ),
+    };
+
+    model::CommitSignature {
+        index,
+        flag,
+        validator_address,
+        timestamp,
+    }
+}
+
+fn commit_to_model(commit: &Commit) -> model::Commit {
+    model::Commit {
+        height: commit.height.value(),
+        signatures: commit
+            .signatures
+            .iter()
+            .enumerate()
+            .map(|(i, sig)| commit_sig_to_model(i as u32, sig))
+            .collect(),
+    }
+}
+
+/// Validators are returned in the same order as commit signatures
+pub fn validator_set_to_model(resp: &rpc::endpoint::validators::Response) -> model::ValidatorSet {
+    model::ValidatorSet {
+        height: resp.block_height.value(),
+        validators: resp
+            .validators
+            .iter()
+            .enumerate()
+            .map(|(i, validator)| model::ValidatorPower {
+                index: i as u32,
+                address: validator.address.to_string(),
+                voting_power: validator.power.value(),
+                proposer_priority: validator.proposer_priority.value(),
+            })
+            .collect(),
+    }
+}
+
+pub fn block_to_model(
+    registry: &Registry,
+    resp: &rpc::endpoint::block::Response,
+    results: &rpc::endpoint::block_results::Response,
+) -> cosmrs::Result<model::Block> {
+    let tx_results = results.txs_results.as_deref().unwrap_or_default();
+    let tx_count = resp.block.data.iter().count();
+
+    if tx_results.len() != tx_count {
+        return Err(cosmrs::ErrorReport::msg(format!(
+            "Block {} has {} txs but {} tx results",
+            resp.block.header.height,
+            tx_count,
+            tx_results.len()
+        )));
+    }
+
     let mut txs = Vec::<model::Tx>::new();
-    for (i, tx) in resp.block.data.iter().enumerate() {
-        txs.push(tx_to_model(i as u32, tx)?)
+    for (i, (tx, result)) in resp.block.data.iter().zip(tx_results).enumerate() {
+        txs.push(tx_to_model(registry, i as u32, tx, result)?)
     }
 
     Ok(model::Block {
@@ -182,6 +305,126 @@ pub fn block_to_model(resp: &rpc::endpoint::block::Response) -> cosmrs::Result<m
         height: resp.block.header.height.value(),
         time: block_time(resp.block.header.time),
         proposer: resp.block.header.proposer_address.to_string(),
-        txs: txs,
+        txs,
+        begin_block_events: events_to_model(
+            results.begin_block_events.as_deref().unwrap_or_default(),
+        ),
+        end_block_events: events_to_model(results.end_block_events.as_deref().unwrap_or_default()),
+        last_commit: resp.block.last_commit.as_ref().map(commit_to_model),
+        archive: None,
     })
 }
+
+const ARCHIVE_LEVEL: i32 = 3;
+
+/// The raw txs and `block_results` of a block, compressed for the archive tables
+pub fn block_to_archive(
+    resp: &rpc::endpoint::block::Response,
+    results: &rpc::endpoint::block_results::Response,
+) -> cosmrs::Result<model::Archive> {
+    let txs = resp
+        .block
+        .data
+        .iter()
+        .map(|tx| zstd::encode_all(tx.as_bytes(), ARCHIVE_LEVEL))
+        .collect::<std::io::Result<_>>()?;
+    let results = zstd::encode_all(&serde_json::to_vec(results)?[..], ARCHIVE_LEVEL)?;
+    Ok(model::Archive { txs, results })
+}
+
+pub fn archived_tx(data: &[u8]) -> cosmrs::Result<Vec<u8>> {
+    Ok(zstd::decode_all(data)?)
+}
+
+/// Decodes the archived txs of a block again, along with their results
+pub fn archived_txs_to_model(
+    registry: &Registry,
+    txs: Vec<Vec<u8>>,
+    results: &rpc::endpoint::block_results::Response,
+) -> cosmrs::Result<Vec<model::Tx>> {
+    let tx_results = results.txs_results.as_deref().unwrap_or_default();
+    if tx_results.len() != txs.len() {
+        return Err(cosmrs::ErrorReport::msg(format!(
+            "Block {} has {} archived txs but {} tx results",
+            results.height,
+            txs.len(),
+            tx_results.len()
+        )));
+    }
+
+    txs.into_iter()
+        .zip(tx_results)
+        .enumerate()
+        .map(|(i, (tx, result))| tx_to_model(registry, i as u32, &Transaction::from(tx), result))
+        .collect()
+}
+
+pub fn archived_block_results(
+    data: &[u8],
+) -> cosmrs::Result<rpc::endpoint::block_results::Response> {
+    Ok(serde_json::from_slice(&zstd::decode_all(data)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(coins: &str) -> Vec<(u128, String)> {
+        parse_coins(coins)
+            .unwrap()
+            .into_iter()
+            .map(|coin| (coin.amount, coin.denom))
+            .collect()
+    }
+
+    #[test]
+    fn parses_event_coins() {
+        assert_eq!(
+            parsed("1a,2ibc/X"),
+            vec![(1, "a".to_string()), (2, "ibc/X".to_string())]
+        );
+        assert_eq!(parsed(" 3uatom , "), vec![(3, "uatom".to_string())]);
+        assert!(parsed("").is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_coins() {
+        // One more than u128::MAX
+        assert!(parse_coins("340282366920938463463374607431768211456uatom").is_err());
+        assert!(parse_coins("100").is_err());
+        assert!(parse_coins("uatom").is_err());
+        assert!(parse_coins("1a,2").is_err());
+    }
+
+    #[test]
+    fn stores_undecodable_messages_without_addresses() {
+        let msg = |value: Vec<u8>| Any {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            value,
+        };
+        let send = cosmos::bank::v1beta1::MsgSend {
+            from_address: "cosmos1a".to_string(),
+            to_address: "cosmos1b".to_string(),
+            amount: vec![],
+        };
+        let tx = cosmos::tx::v1beta1::Tx {
+            body: Some(cosmos::tx::v1beta1::TxBody {
+                messages: vec![msg(vec![0xff, 0xff]), msg(send.encode_to_vec())],
+                ..Default::default()
+            }),
+            auth_info: Some(Default::default()),
+            signatures: vec![],
+        };
+        let tx = Transaction::from(tx.encode_to_vec());
+
+        let result: DeliverTx = serde_json::from_value(serde_json::json!({
+            "code": 0, "data": null, "log": "", "info": "", "events": [], "codespace": ""
+        }))
+        .unwrap();
+
+        let tx = tx_to_model(&Registry::default(), 0, &tx, &result).unwrap();
+
+        let addresses: Vec<usize> = tx.msgs.iter().map(|it| it.addresses.len()).collect();
+        assert_eq!(addresses, vec![0, 2]);
+    }
+}
@@ -0,0 +1,335 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::args::Args;
+use crate::decoder::staking;
+use crate::model;
+use crate::tables;
+
+use crate::tables::genesis::{
+    GenesisAccountRow, GenesisBalanceRow, GenesisDelegationRow, GenesisParamRow, GenesisRow,
+    GenesisValidatorRow,
+};
+use crate::tables::validator::ValidatorRow;
+
+/// The parts of a genesis file the indexer makes use of
+#[derive(Debug, Deserialize)]
+pub struct Genesis {
+    pub chain_id: String,
+    pub genesis_time: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub initial_height: u64,
+    pub app_state: AppState,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AppState {
+    pub auth: Auth,
+    pub bank: Bank,
+    pub staking: Staking,
+    #[serde(default)]
+    pub gov: serde_json::Map<String, Value>,
+}
+
+/// Accounts come in many types, they are walked as JSON
+#[derive(Debug, Deserialize)]
+pub struct Auth {
+    pub accounts: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Bank {
+    pub balances: Vec<Balance>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Balance {
+    pub address: String,
+    pub coins: Vec<Coin>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Coin {
+    pub denom: String,
+    pub amount: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Staking {
+    pub params: Value,
+    pub validators: Vec<Validator>,
+    pub delegations: Vec<Delegation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Validator {
+    pub operator_address: String,
+    pub consensus_pubkey: PubKey,
+    pub jailed: bool,
+    pub status: String,
+    pub tokens: String,
+    pub delegator_shares: String,
+    pub description: Description,
+    pub commission: Commission,
+    pub min_self_delegation: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PubKey {
+    #[serde(rename = "@type")]
+    pub type_url: String,
+    #[serde(default)]
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Description {
+    pub moniker: String,
+    pub identity: String,
+    pub website: String,
+    pub security_contact: String,
+    pub details: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Commission {
+    pub commission_rates: CommissionRates,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommissionRates {
+    pub rate: String,
+    pub max_rate: String,
+    pub max_change_rate: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Delegation {
+    pub delegator_address: String,
+    pub validator_address: String,
+    pub shares: String,
+}
+
+/// Heights are strings in most genesis files, plain numbers in some
+fn string_or_number<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Height {
+        String(String),
+        Number(u64),
+    }
+
+    match Height::deserialize(deserializer)? {
+        Height::String(s) => s.parse().map_err(serde::de::Error::custom),
+        Height::Number(n) => Ok(n),
+    }
+}
+
+impl Genesis {
+    /// Height of the state described by the file, the first block comes right after it
+    pub fn height(&self) -> u64 {
+        self.initial_height.saturating_sub(1)
+    }
+}
+
+impl Coin {
+    pub fn to_model(&self) -> cosmrs::Result<model::Coin> {
+        Ok(model::Coin {
+            denom: self.denom.clone(),
+            amount: self.amount.parse()?,
+        })
+    }
+}
+
+impl Validator {
+    /// Consensus key as hex, along with the address Tendermint reports as block proposer
+    fn consensus_key(&self) -> cosmrs::Result<(Option<String>, Option<String>)> {
+        match self.consensus_pubkey.type_url.as_str() {
+            "/cosmos.crypto.ed25519.PubKey" => {
+                let key = base64::decode(&self.consensus_pubkey.key)?;
+                Ok((
+                    Some(hex::encode(&key)),
+                    Some(staking::ed25519_address(&key)),
+                ))
+            }
+            _ => Ok((None, None)),
+        }
+    }
+
+    fn to_model(&self) -> cosmrs::Result<model::Validator> {
+        let (consensus_pubkey, consensus_address) = self.consensus_key()?;
+        let description = &self.description;
+        let rates = &self.commission.commission_rates;
+        Ok(model::Validator {
+            operator_address: self.operator_address.clone(),
+            consensus_pubkey,
+            consensus_address,
+            moniker: Some(description.moniker.clone()),
+            identity: Some(description.identity.clone()),
+            website: Some(description.website.clone()),
+            security_contact: Some(description.security_contact.clone()),
+            details: Some(description.details.clone()),
+            commission_rate: Some(rates.rate.clone()),
+            commission_max_rate: Some(rates.max_rate.clone()),
+            commission_max_change_rate: Some(rates.max_change_rate.clone()),
+            min_self_delegation: Some(self.min_self_delegation.clone()),
+        })
+    }
+}
+
+/// Integer part of a decimal such as the `1000.000000000000000000` shares of a delegation
+fn integer_part(decimal: &str) -> cosmrs::Result<u128> {
+    Ok(decimal.split('.').next().unwrap_or_default().parse()?)
+}
+
+/// Tokens represented by delegation shares, fractions of a token are dropped
+fn delegation_tokens(shares: &str, validator: &Validator) -> cosmrs::Result<u128> {
+    let shares = integer_part(shares)?;
+    let tokens: u128 = validator.tokens.parse()?;
+    let total = integer_part(&validator.delegator_shares)?;
+    if total == 0 {
+        return Ok(0);
+    }
+    Ok(shares
+        .checked_mul(tokens)
+        .map(|it| it / total)
+        .unwrap_or(shares))
+}
+
+/// Module, vesting and base accounts nest the base account at different depths
+fn base_account(account: &Value) -> &Value {
+    account
+        .get("base_account")
+        .or_else(|| account.get("base_vesting_account"))
+        .map(base_account)
+        .unwrap_or(account)
+}
+
+fn account_row(account: &Value) -> GenesisAccountRow {
+    let base = base_account(account);
+    let string =
+        |value: &Value, key: &str| value.get(key).and_then(Value::as_str).map(String::from);
+    GenesisAccountRow {
+        address: string(base, "address").unwrap_or_default(),
+        kind: string(account, "@type").unwrap_or_default(),
+        account_number: string(base, "account_number"),
+        sequence: string(base, "sequence"),
+        name: string(account, "name"),
+    }
+}
+
+fn param_row(module: &str, key: &str, value: &Value) -> GenesisParamRow {
+    GenesisParamRow {
+        module: module.to_string(),
+        key: key.to_string(),
+        value: value.to_string(),
+    }
+}
+
+pub fn load(path: &Path) -> cosmrs::Result<Genesis> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Imports the accounts, balances, validators, delegations and parameters of a genesis file.
+/// A database holds a single genesis, importing another one is refused.
+pub fn import(args: &Args, path: &Path) -> cosmrs::Result<()> {
+    let genesis = load(path)?;
+    let height = genesis.height();
+
+    let mut conn = tables::schema::conn(&args.datadir)?;
+    let mut txn = conn.transaction()?;
+
+    if let Some(existing) = tables::genesis::get(&mut txn)? {
+        return Err(cosmrs::ErrorReport::msg(format!(
+            "Genesis of {} already imported",
+            existing.chain_id
+        )));
+    }
+
+    let row = &GenesisRow {
+        chain_id: genesis.chain_id.clone(),
+        height,
+        time: genesis.genesis_time.clone(),
+    };
+    tables::genesis::insert(&mut txn, row)?;
+
+    for account in &genesis.app_state.auth.accounts {
+        tables::genesis::insert_account(&mut txn, &account_row(account))?;
+    }
+
+    for balance in &genesis.app_state.bank.balances {
+        for coin in &balance.coins {
+            let row = &GenesisBalanceRow {
+                address: balance.address.clone(),
+                denom: coin.denom.clone(),
+                amount: coin.to_model()?.amount,
+            };
+            tables::genesis::insert_balance(&mut txn, row)?;
+        }
+    }
+
+    let staking = &genesis.app_state.staking;
+    let mut validators = HashMap::<&str, &Validator>::new();
+    for validator in &staking.validators {
+        let model = validator.to_model()?;
+        let row = &GenesisValidatorRow {
+            operator_address: validator.operator_address.clone(),
+            consensus_pubkey: model.consensus_pubkey.clone(),
+            consensus_address: model.consensus_address.clone(),
+            moniker: validator.description.moniker.clone(),
+            status: validator.status.clone(),
+            jailed: validator.jailed,
+            tokens: validator.tokens.parse()?,
+            delegator_shares: validator.delegator_shares.clone(),
+            commission_rate: validator.commission.commission_rates.rate.clone(),
+        };
+        tables::genesis::insert_validator(&mut txn, row)?;
+        tables::validator::upsert(&mut txn, &ValidatorRow::new(height, false, &model))?;
+        validators.insert(&validator.operator_address, validator);
+    }
+
+    let bond_denom = staking.params["bond_denom"].as_str().unwrap_or_default();
+    for delegation in &staking.delegations {
+        let amount = match validators.get(delegation.validator_address.as_str()) {
+            Some(validator) => delegation_tokens(&delegation.shares, validator)?,
+            None => 0,
+        };
+        let row = &GenesisDelegationRow {
+            delegator: delegation.delegator_address.clone(),
+            validator: delegation.validator_address.clone(),
+            shares: delegation.shares.clone(),
+            denom: bond_denom.to_string(),
+            amount,
+        };
+        tables::genesis::insert_delegation(&mut txn, row)?;
+    }
+
+    tables::genesis::insert_param(&mut txn, &param_row("staking", "params", &staking.params))?;
+    // v1beta1 splits gov params in three, v1 has a single `params`
+    for (key, value) in &genesis.app_state.gov {
+        if key.ends_with("params") && !value.is_null() {
+            tables::genesis::insert_param(&mut txn, &param_row("gov", key, value))?;
+        }
+    }
+
+    txn.commit()?;
+
+    log::info!(
+        "Imported genesis of {} at height {} : {} accounts, {} balances, {} validators, {} delegations",
+        genesis.chain_id,
+        height,
+        genesis.app_state.auth.accounts.len(),
+        genesis.app_state.bank.balances.len(),
+        staking.validators.len(),
+        staking.delegations.len()
+    );
+    Ok(())
+}
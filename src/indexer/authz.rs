@@ -0,0 +1,58 @@
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::authz_grant::AuthzGrantRow;
+
+pub const CURSOR: &str = "authz";
+
+fn index_authz_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    for msg in &indexer::successful_msgs(txn, height)? {
+        for action in indexer::msg_actions(registry, msg) {
+            match action {
+                model::Action::Grant(grant) => {
+                    // A new grant for the same message type replaces the previous one
+                    let (granter, grantee) = (&grant.granter, &grant.grantee);
+                    tables::authz_grant::revoke(
+                        txn,
+                        granter,
+                        grantee,
+                        &grant.msg_type_url,
+                        height,
+                    )?;
+                    tables::authz_grant::insert(txn, &AuthzGrantRow::new(msg, &grant))?;
+                }
+                model::Action::Revoke(revoke) => {
+                    let (granter, grantee) = (&revoke.granter, &revoke.grantee);
+                    tables::authz_grant::revoke(
+                        txn,
+                        granter,
+                        grantee,
+                        &revoke.msg_type_url,
+                        height,
+                    )?;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Removes what was derived from `block` onward
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    tables::authz_grant::delete_since(txn, block)
+}
+
+pub async fn index_authz(args: &Args, registry: &Registry) {
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_authz_block(txn, registry, height)
+    })
+    .await
+}
@@ -0,0 +1,42 @@
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::delegation_change::DelegationChangeRow;
+
+pub const CURSOR: &str = "delegation";
+
+fn index_delegation_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    for msg in &indexer::successful_msgs(txn, height)? {
+        let changes = indexer::msg_actions(registry, msg)
+            .into_iter()
+            .filter_map(|action| match action {
+                model::Action::DelegationChange(change) => Some(change),
+                _ => None,
+            });
+
+        for (i, change) in changes.enumerate() {
+            let row = &DelegationChangeRow::new(msg, i as u32, &change);
+            tables::delegation_change::insert(txn, row)?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes what was derived from `block` onward
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    tables::delegation_change::delete_since(txn, block)
+}
+
+pub async fn index_delegation(args: &Args, registry: &Registry) {
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_delegation_block(txn, registry, height)
+    })
+    .await
+}
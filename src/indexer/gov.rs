@@ -0,0 +1,226 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::msg::MsgRow;
+use crate::tables::proposal::ProposalRow;
+use crate::tables::proposal_deposit::ProposalDepositRow;
+use crate::tables::proposal_vote::ProposalVoteRow;
+
+pub const CURSOR: &str = "gov";
+
+fn insert_deposits(
+    txn: &mut rusqlite::Transaction,
+    msg: &MsgRow,
+    proposal_id: u64,
+    depositor: &str,
+    amount: &[model::Coin],
+) -> rusqlite::Result<()> {
+    for (i, coin) in amount.iter().enumerate() {
+        let row = &ProposalDepositRow::new(msg, i as u32, proposal_id, depositor, coin);
+        tables::proposal_deposit::insert(txn, row)?;
+    }
+    Ok(())
+}
+
+fn index_gov_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    // Proposal ids are assigned on execution, they come from the events of the tx in message order
+    let mut proposal_ids = HashMap::<u32, std::vec::IntoIter<String>>::new();
+
+    for msg in &indexer::successful_msgs(txn, height)? {
+        for action in &indexer::msg_actions(registry, msg) {
+            match action {
+                model::Action::SubmitProposal(proposal) => {
+                    if let Entry::Vacant(entry) = proposal_ids.entry(msg.tx) {
+                        let ids = tables::event::attribute_values(
+                            txn,
+                            height,
+                            msg.tx,
+                            "submit_proposal",
+                            "proposal_id",
+                        )?;
+                        entry.insert(ids.into_iter());
+                    }
+
+                    let id = proposal_ids
+                        .get_mut(&msg.tx)
+                        .and_then(|ids| ids.next())
+                        .and_then(|id| id.parse().ok());
+
+                    match id {
+                        Some(id) => {
+                            let row = &ProposalRow::new(msg, id, proposal);
+                            tables::proposal::insert(txn, row)?;
+                            let deposit = &proposal.initial_deposit;
+                            insert_deposits(txn, msg, id, &proposal.proposer, deposit)?;
+                        }
+                        None => log::warn!(
+                            "No proposal id for message {}/{}/{}",
+                            msg.block,
+                            msg.tx,
+                            msg.idx
+                        ),
+                    }
+                }
+                model::Action::Deposit(deposit) => {
+                    let (id, depositor) = (deposit.proposal_id, &deposit.depositor);
+                    insert_deposits(txn, msg, id, depositor, &deposit.amount)?;
+                }
+                model::Action::Vote(vote) => {
+                    for (i, option) in vote.options.iter().enumerate() {
+                        let row = &ProposalVoteRow::new(msg, i as u32, vote, option);
+                        tables::proposal_vote::insert(txn, row)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Removes what was derived from `block` onward
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    tables::proposal::delete_since(txn, block)?;
+    tables::proposal_deposit::delete_since(txn, block)?;
+    tables::proposal_vote::delete_since(txn, block)
+}
+
+pub async fn index_gov(args: &Args, registry: &Registry) {
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_gov_block(txn, registry, height)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmrs::proto::cosmos::gov::v1beta1::{
+        MsgVote, MsgVoteWeighted, VoteOption, WeightedVoteOption,
+    };
+    use prost::Message;
+
+    const YES: i32 = VoteOption::Yes as i32;
+    const NO: i32 = VoteOption::No as i32;
+    const ABSTAIN: i32 = VoteOption::Abstain as i32;
+    const NO_WITH_VETO: i32 = VoteOption::NoWithVeto as i32;
+
+    /// Stores the message as the history indexer would, then runs the gov indexer on its block
+    fn index(txn: &mut rusqlite::Transaction, block: u64, tag: &str, data: Vec<u8>) {
+        let msg = &MsgRow {
+            block,
+            tx: 0,
+            idx: 0,
+            parent: None,
+            tag: tag.to_string(),
+            data,
+        };
+        tables::msg::insert(txn, msg).unwrap();
+        index_gov_block(txn, &Registry::default(), block).unwrap();
+    }
+
+    fn vote(
+        txn: &mut rusqlite::Transaction,
+        block: u64,
+        proposal_id: u64,
+        voter: &str,
+        option: i32,
+    ) {
+        let msg = MsgVote {
+            proposal_id,
+            voter: voter.to_string(),
+            option,
+        };
+        index(
+            txn,
+            block,
+            "/cosmos.gov.v1beta1.MsgVote",
+            msg.encode_to_vec(),
+        );
+    }
+
+    /// Weights are given as they go on the wire, 10^18 scaled
+    fn vote_weighted(
+        txn: &mut rusqlite::Transaction,
+        block: u64,
+        proposal_id: u64,
+        voter: &str,
+        options: &[(i32, &str)],
+    ) {
+        let msg = MsgVoteWeighted {
+            proposal_id,
+            voter: voter.to_string(),
+            options: options
+                .iter()
+                .map(|(option, weight)| WeightedVoteOption {
+                    option: *option,
+                    weight: weight.to_string(),
+                })
+                .collect(),
+        };
+        let tag = "/cosmos.gov.v1beta1.MsgVoteWeighted";
+        index(txn, block, tag, msg.encode_to_vec());
+    }
+
+    fn tally(txn: &mut rusqlite::Transaction, proposal_id: u64) -> Vec<(String, f64)> {
+        tables::proposal_vote::tally(txn, proposal_id)
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.option, row.weight))
+            .collect()
+    }
+
+    fn options(options: &[(&str, f64)]) -> Vec<(String, f64)> {
+        options
+            .iter()
+            .map(|(option, weight)| (option.to_string(), *weight))
+            .collect()
+    }
+
+    #[test]
+    fn tally_counts_the_latest_weighted_vote_of_each_voter() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        tables::schema::create(&conn).unwrap();
+        let mut txn = conn.transaction().unwrap();
+
+        vote(&mut txn, 10, 1, "cosmos1a", YES);
+        let weights = [(YES, "700000000000000000"), (NO, "300000000000000000")];
+        vote_weighted(&mut txn, 11, 1, "cosmos1b", &weights);
+        vote(&mut txn, 12, 2, "cosmos1c", ABSTAIN);
+
+        let weights: Vec<Vec<String>> = txn
+            .prepare("SELECT weight FROM proposal_vote WHERE block = 11 ORDER BY idx")
+            .unwrap()
+            .query_map([], |row| Ok(vec![row.get(0)?]))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            weights,
+            [["0.700000000000000000"], ["0.300000000000000000"]]
+        );
+        assert_eq!(tally(&mut txn, 1), options(&[("no", 0.3), ("yes", 1.7)]));
+
+        // A change of mind replaces every option of the earlier vote
+        vote(&mut txn, 20, 1, "cosmos1b", NO_WITH_VETO);
+        assert_eq!(
+            tally(&mut txn, 1),
+            options(&[("no_with_veto", 1.0), ("yes", 1.0)])
+        );
+        assert_eq!(tables::proposal_vote::voters(&mut txn, 1).unwrap(), 2);
+
+        rewind(&mut txn, 20).unwrap();
+        assert_eq!(tally(&mut txn, 1), options(&[("no", 0.3), ("yes", 1.7)]));
+        assert_eq!(tally(&mut txn, 2), options(&[("abstain", 1.0)]));
+    }
+}
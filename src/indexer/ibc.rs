@@ -0,0 +1,221 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::ibc_transfer::{self as ibc_transfer, IbcTransferRow};
+
+pub const CURSOR: &str = "ibc";
+
+/// Picks the first unconsumed `send_packet` event emitted for the transfer's channel
+fn take_send_packet(
+    events: &mut Vec<HashMap<String, String>>,
+    transfer: &model::IbcTransfer,
+) -> Option<HashMap<String, String>> {
+    let position = events.iter().position(|event| {
+        event.get("packet_src_port") == Some(&transfer.source_port)
+            && event.get("packet_src_channel") == Some(&transfer.source_channel)
+    })?;
+    Some(events.remove(position))
+}
+
+fn index_ibc_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    let mut send_packets = HashMap::<u32, Vec<HashMap<String, String>>>::new();
+
+    for msg in &indexer::successful_msgs(txn, height)? {
+        for action in indexer::msg_actions(registry, msg) {
+            match action {
+                model::Action::IbcSend(mut transfer) => {
+                    let events = match send_packets.entry(msg.tx) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            entry.insert(indexer::tx_events(txn, height, msg.tx, "send_packet")?)
+                        }
+                    };
+
+                    let event = take_send_packet(events, &transfer);
+                    let sequence = event
+                        .as_ref()
+                        .and_then(|it| it.get("packet_sequence"))
+                        .and_then(|it| it.parse().ok());
+
+                    match (event, sequence) {
+                        (Some(event), Some(sequence)) => {
+                            transfer.destination_port = event.get("packet_dst_port").cloned();
+                            transfer.destination_channel = event.get("packet_dst_channel").cloned();
+                            let row = &IbcTransferRow::new(
+                                msg,
+                                ibc_transfer::OUTBOUND,
+                                ibc_transfer::SENT,
+                                sequence,
+                                &transfer,
+                            );
+                            tables::ibc_transfer::insert(txn, row)?;
+                        }
+                        _ => log::warn!(
+                            "No packet sequence for message {}/{}/{}",
+                            msg.block,
+                            msg.tx,
+                            msg.idx
+                        ),
+                    }
+                }
+                model::Action::IbcReceive(transfer) => {
+                    let row = &IbcTransferRow::new(
+                        msg,
+                        ibc_transfer::INBOUND,
+                        ibc_transfer::RECEIVED,
+                        transfer.sequence.unwrap_or_default(),
+                        &transfer,
+                    );
+                    tables::ibc_transfer::insert(txn, row)?;
+                }
+                model::Action::IbcAcknowledge(packet, success) => {
+                    let status = if success {
+                        ibc_transfer::ACKED
+                    } else {
+                        ibc_transfer::ACK_ERROR
+                    };
+                    let direction = ibc_transfer::OUTBOUND;
+                    tables::ibc_transfer::set_status(txn, direction, &packet, status, height)?;
+                }
+                model::Action::IbcTimeout(packet) => {
+                    let (direction, status) = (ibc_transfer::OUTBOUND, ibc_transfer::TIMED_OUT);
+                    tables::ibc_transfer::set_status(txn, direction, &packet, status, height)?;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Removes what was derived from `block` onward
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    tables::ibc_transfer::delete_since(txn, block)
+}
+
+pub async fn index_ibc(args: &Args, registry: &Registry) {
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_ibc_block(txn, registry, height)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::msg::MsgRow;
+
+    fn packet(sequence: u64) -> model::IbcPacket {
+        model::IbcPacket {
+            source_port: "transfer".to_string(),
+            source_channel: "channel-0".to_string(),
+            sequence,
+        }
+    }
+
+    fn insert(
+        txn: &mut rusqlite::Transaction,
+        block: u64,
+        direction: &str,
+        status: &str,
+        sequence: u64,
+    ) {
+        let msg = &MsgRow {
+            block,
+            tx: 0,
+            idx: 0,
+            parent: None,
+            tag: String::new(),
+            data: vec![],
+        };
+        let transfer = &model::IbcTransfer {
+            source_port: "transfer".to_string(),
+            source_channel: "channel-0".to_string(),
+            sequence: Some(sequence),
+            destination_port: None,
+            destination_channel: None,
+            sender: "cosmos1a".to_string(),
+            receiver: "osmo1b".to_string(),
+            denom: "uatom".to_string(),
+            amount: 10,
+        };
+        let row = &IbcTransferRow::new(msg, direction, status, sequence, transfer);
+        tables::ibc_transfer::insert(txn, row).unwrap();
+    }
+
+    fn statuses(txn: &mut rusqlite::Transaction) -> Vec<(String, u64, String, u64)> {
+        txn.prepare("SELECT direction, sequence, status, updated FROM ibc_transfer ORDER BY direction, sequence")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    fn status(
+        direction: &str,
+        sequence: u64,
+        status: &str,
+        updated: u64,
+    ) -> (String, u64, String, u64) {
+        (direction.to_string(), sequence, status.to_string(), updated)
+    }
+
+    #[test]
+    fn statuses_move_on_outbound_transfers_and_back_on_rewind() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        tables::schema::create(&conn).unwrap();
+        let mut txn = conn.transaction().unwrap();
+
+        let (out, inbound) = (ibc_transfer::OUTBOUND, ibc_transfer::INBOUND);
+        insert(&mut txn, 10, out, ibc_transfer::SENT, 1);
+        insert(&mut txn, 10, out, ibc_transfer::SENT, 2);
+        insert(&mut txn, 10, inbound, ibc_transfer::RECEIVED, 1);
+
+        // Acknowledgements and timeouts only concern the transfers sent from this chain
+        let set_status = |txn: &mut rusqlite::Transaction, sequence, status, block| {
+            tables::ibc_transfer::set_status(txn, out, &packet(sequence), status, block).unwrap()
+        };
+        set_status(&mut txn, 1, ibc_transfer::ACKED, 20);
+        set_status(&mut txn, 2, ibc_transfer::TIMED_OUT, 30);
+        set_status(&mut txn, 3, ibc_transfer::ACK_ERROR, 30);
+        assert_eq!(
+            statuses(&mut txn),
+            vec![
+                status(inbound, 1, ibc_transfer::RECEIVED, 10),
+                status(out, 1, ibc_transfer::ACKED, 20),
+                status(out, 2, ibc_transfer::TIMED_OUT, 30),
+            ]
+        );
+
+        rewind(&mut txn, 30).unwrap();
+        assert_eq!(
+            statuses(&mut txn),
+            vec![
+                status(inbound, 1, ibc_transfer::RECEIVED, 10),
+                status(out, 1, ibc_transfer::ACKED, 20),
+                status(out, 2, ibc_transfer::SENT, 10),
+            ]
+        );
+
+        rewind(&mut txn, 15).unwrap();
+        assert_eq!(
+            statuses(&mut txn),
+            vec![
+                status(inbound, 1, ibc_transfer::RECEIVED, 10),
+                status(out, 1, ibc_transfer::SENT, 10),
+                status(out, 2, ibc_transfer::SENT, 10),
+            ]
+        );
+    }
+}
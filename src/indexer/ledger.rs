@@ -0,0 +1,694 @@
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use tokio::time::sleep;
+
+use crate::args::Args;
+use crate::decoder::{bank, Registry};
+use crate::fetch;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::balance::BalanceRow;
+use crate::tables::ledger_entry::{self as ledger_entry, LedgerEntryRow};
+use crate::tables::msg::MsgRow;
+use crate::tables::tx::TxRow;
+
+pub const CURSOR: &str = "ledger";
+
+/// Writes both sides of each movement of a block, numbered in the order they are recorded,
+/// and keeps the running balances of both parties
+struct Ledger {
+    block: u64,
+    idx: u32,
+}
+
+impl Ledger {
+    #[allow(clippy::too_many_arguments)]
+    fn record(
+        &mut self,
+        txn: &mut rusqlite::Transaction,
+        tx: Option<u32>,
+        msg: Option<u32>,
+        kind: &str,
+        sender: &str,
+        receiver: &str,
+        coin: &model::Coin,
+    ) -> rusqlite::Result<()> {
+        // Multi-send inputs and outputs only have a side each
+        for (address, counterparty, debit) in [(sender, receiver, true), (receiver, sender, false)]
+        {
+            if address == bank::MULTI {
+                continue;
+            }
+            let row = &LedgerEntryRow::new(
+                self.block,
+                self.idx,
+                tx,
+                msg,
+                kind,
+                address,
+                counterparty,
+                coin,
+                debit,
+            );
+            ledger_entry::insert(txn, row)?;
+            self.idx += 1;
+
+            let delta = row.credit as i128 - row.debit as i128;
+            let amount = tables::balance::get(txn, address, &coin.denom)?
+                .map(|it| it.amount)
+                .unwrap_or_default();
+            let row = &BalanceRow::new(self.block, address, &coin.denom, amount + delta);
+            tables::balance::set(txn, row)?;
+        }
+        Ok(())
+    }
+}
+
+/// One coin moved by the bank module. A side is missing when nothing tells it apart :
+/// mints, burns and the receivers of multi-sends with several inputs.
+#[derive(Debug)]
+struct Movement {
+    sender: Option<String>,
+    receiver: Option<String>,
+    coin: model::Coin,
+}
+
+/// Pairs the `coin_spent` and `coin_received` events that the bank module emits for every
+/// balance change, `transfer` events leave out delegations, mints and burns. A send emits one
+/// right after the other, each receipt is paired with the latest spend of the denom covering it.
+fn movements(block: u64, events: &[(String, HashMap<String, String>)]) -> Vec<Movement> {
+    let mut moved = Vec::new();
+    let mut pending: Vec<(String, model::Coin)> = Vec::new();
+
+    for (kind, event) in events {
+        let get = |key: &str| event.get(key).map(String::as_str).unwrap_or_default();
+        let address = match kind.as_str() {
+            "coin_spent" => get("spender"),
+            "coin_received" => get("receiver"),
+            _ => continue,
+        };
+        let coins = match fetch::parse_coins(get("amount")) {
+            Ok(coins) => coins.into_iter().filter(|coin| coin.amount > 0),
+            Err(err) => {
+                log::warn!("Unparsable {} amount in block {} : {}", kind, block, err);
+                continue;
+            }
+        };
+
+        if kind == "coin_spent" {
+            pending.extend(coins.map(|coin| (address.to_string(), coin)));
+            continue;
+        }
+        for coin in coins {
+            let spend = pending
+                .iter()
+                .rposition(|(_, it)| it.denom == coin.denom && it.amount >= coin.amount);
+            let sender = spend.map(|position| {
+                let (sender, spent) = &mut pending[position];
+                spent.amount -= coin.amount;
+                let sender = sender.clone();
+                if spent.amount == 0 {
+                    pending.remove(position);
+                }
+                sender
+            });
+            moved.push(Movement {
+                sender,
+                receiver: Some(address.to_string()),
+                coin,
+            });
+        }
+    }
+
+    // Spent and never received, burnt or sent to several receivers
+    moved.extend(pending.into_iter().map(|(sender, coin)| Movement {
+        sender: Some(sender),
+        receiver: None,
+        coin,
+    }));
+    moved
+}
+
+/// Kind of a movement that no message or event names
+fn default_kind(movement: &Movement) -> &'static str {
+    match (&movement.sender, &movement.receiver) {
+        (None, Some(_)) => ledger_entry::MINT,
+        (Some(_), None) => ledger_entry::BURN,
+        _ => ledger_entry::TRANSFER,
+    }
+}
+
+/// What a message or an event says is moved, it names the first movement matching it
+#[derive(Debug)]
+struct Label {
+    kind: &'static str,
+    sender: Option<String>,
+    receiver: Option<String>,
+    coin: model::Coin,
+}
+
+impl Label {
+    fn new(
+        kind: &'static str,
+        sender: Option<&str>,
+        receiver: Option<&str>,
+        coin: model::Coin,
+    ) -> Self {
+        // Multi-sends leave the other side of each input and output unknown
+        let side =
+            |address: Option<&str>| address.filter(|it| *it != bank::MULTI).map(str::to_string);
+        Label {
+            kind,
+            sender: side(sender),
+            receiver: side(receiver),
+            coin,
+        }
+    }
+
+    fn matches(&self, movement: &Movement) -> bool {
+        let side =
+            |label: &Option<String>, moved: &Option<String>| label.is_none() || label == moved;
+        self.coin.denom == movement.coin.denom
+            && self.coin.amount == movement.coin.amount
+            && side(&self.sender, &movement.sender)
+            && side(&self.receiver, &movement.receiver)
+    }
+
+    /// Labels naming both parties are tried first, then those naming one, then the others
+    fn sorted(mut labels: Vec<Label>) -> Vec<Label> {
+        labels.sort_by_key(|label| {
+            std::cmp::Reverse(label.sender.is_some() as u8 + label.receiver.is_some() as u8)
+        });
+        labels
+    }
+}
+
+fn labels(
+    kind: &'static str,
+    sender: Option<&str>,
+    receiver: Option<&str>,
+    coins: &[model::Coin],
+) -> Vec<Label> {
+    coins
+        .iter()
+        .map(|coin| Label::new(kind, sender, receiver, coin.clone()))
+        .collect()
+}
+
+/// Amounts named by module events, rewards paid out on delegation changes included
+fn event_labels(block: u64, events: &[(String, HashMap<String, String>)]) -> Vec<Label> {
+    let mut labeled = Vec::new();
+    for (kind, event) in events {
+        let get = |key: &str| event.get(key).map(String::as_str);
+        let (kind, receiver) = match kind.as_str() {
+            "withdraw_rewards" => (ledger_entry::REWARD, None),
+            "withdraw_commission" => (ledger_entry::COMMISSION, None),
+            // Matured unbondings are paid out at end block, outside of any tx
+            "complete_unbonding" => (ledger_entry::UNDELEGATE, get("delegator")),
+            _ => continue,
+        };
+        match fetch::parse_coins(get("amount").unwrap_or_default()) {
+            Ok(coins) => labeled.extend(labels(kind, None, receiver, &coins)),
+            Err(err) => log::warn!("Unparsable {} amount in block {} : {}", kind, block, err),
+        }
+    }
+    labeled
+}
+
+/// Denomination of an ICS-20 token on this chain given its full trace path
+fn local_denom(path: &str) -> String {
+    if path.contains('/') {
+        let mut digest = Sha256::new();
+        digest.update(path.as_bytes());
+        format!("ibc/{}", hex::encode_upper(digest.finalize()))
+    } else {
+        path.to_string()
+    }
+}
+
+/// Tokens coming back to the chain that sent them lose a hop, others gain the local one
+fn received_denom(transfer: &model::IbcTransfer) -> String {
+    let source = format!("{}/{}/", transfer.source_port, transfer.source_channel);
+    match transfer.denom.strip_prefix(&source) {
+        Some(denom) => local_denom(denom),
+        None => local_denom(&format!(
+            "{}/{}/{}",
+            transfer.destination_port.as_deref().unwrap_or_default(),
+            transfer.destination_channel.as_deref().unwrap_or_default(),
+            transfer.denom
+        )),
+    }
+}
+
+fn ibc_coin(denom: String, amount: u128) -> [model::Coin; 1] {
+    [model::Coin { denom, amount }]
+}
+
+/// Redelegations carry a negative and a positive change per denom, paired in order. Delegated
+/// tokens go to the bonded pool, and only leave it between pools or once unbonded.
+fn delegation_labels(changes: Vec<model::DelegationChange>) -> Vec<Label> {
+    let (mut outs, ins): (Vec<_>, Vec<_>) = changes.into_iter().partition(|it| it.amount < 0);
+    let mut labeled = Vec::new();
+
+    for change in ins {
+        let coin = model::Coin {
+            denom: change.denom.clone(),
+            amount: change.amount as u128,
+        };
+        match outs.iter().position(|out| out.denom == change.denom) {
+            Some(position) => {
+                outs.remove(position);
+                labeled.push(Label::new(ledger_entry::REDELEGATE, None, None, coin));
+            }
+            None => {
+                let delegator = Some(change.delegator.as_str());
+                labeled.push(Label::new(ledger_entry::DELEGATE, delegator, None, coin));
+            }
+        }
+    }
+    for change in outs {
+        let coin = model::Coin {
+            denom: change.denom,
+            amount: change.amount.unsigned_abs(),
+        };
+        labeled.push(Label::new(ledger_entry::UNDELEGATE, None, None, coin));
+    }
+    labeled
+}
+
+/// What a message and the ones it wraps say they move
+fn msg_labels(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    msgs: &[&MsgRow],
+) -> rusqlite::Result<Vec<Label>> {
+    let mut labeled = Vec::new();
+
+    for msg in msgs {
+        for transfer in indexer::msg_transfers(txn, registry, msg)? {
+            let coin = model::Coin {
+                denom: transfer.denom,
+                amount: transfer.amount,
+            };
+            let (sender, receiver) = (
+                Some(transfer.sender.as_str()),
+                Some(transfer.receiver.as_str()),
+            );
+            labeled.push(Label::new(ledger_entry::TRANSFER, sender, receiver, coin));
+        }
+
+        let mut changes = Vec::new();
+        for action in indexer::msg_actions(registry, msg) {
+            match action {
+                model::Action::DelegationChange(change) => changes.push(change),
+                // Deposits are held by the gov module, funding by the distribution module
+                model::Action::SubmitProposal(proposal) => {
+                    let (kind, sender) =
+                        (ledger_entry::GOV_DEPOSIT, Some(proposal.proposer.as_str()));
+                    labeled.extend(labels(kind, sender, None, &proposal.initial_deposit));
+                }
+                model::Action::Deposit(deposit) => {
+                    let (kind, sender) =
+                        (ledger_entry::GOV_DEPOSIT, Some(deposit.depositor.as_str()));
+                    labeled.extend(labels(kind, sender, None, &deposit.amount));
+                }
+                model::Action::FundCommunityPool(funding) => {
+                    let (kind, sender) = (
+                        ledger_entry::COMMUNITY_POOL,
+                        Some(funding.depositor.as_str()),
+                    );
+                    labeled.extend(labels(kind, sender, None, &funding.amount));
+                }
+                // Tokens are escrowed, or sent to the transfer module and burnt when they are vouchers
+                model::Action::IbcSend(transfer) => {
+                    let coin = ibc_coin(transfer.denom, transfer.amount);
+                    let sender = Some(transfer.sender.as_str());
+                    labeled.extend(labels(ledger_entry::IBC_SEND, sender, None, &coin));
+                }
+                model::Action::IbcReceive(transfer) => {
+                    let coin = ibc_coin(received_denom(&transfer), transfer.amount);
+                    let receiver = Some(transfer.receiver.as_str());
+                    labeled.extend(labels(ledger_entry::IBC_RECEIVE, None, receiver, &coin));
+                }
+                model::Action::IbcRefund(transfer) => {
+                    let coin = ibc_coin(local_denom(&transfer.denom), transfer.amount);
+                    let receiver = Some(transfer.sender.as_str());
+                    labeled.extend(labels(ledger_entry::IBC_REFUND, None, receiver, &coin));
+                }
+                _ => {}
+            }
+        }
+        labeled.extend(delegation_labels(changes));
+    }
+    Ok(labeled)
+}
+
+/// Records the movements of `events`, named by the first label matching each of them
+/// or else by `kind` when given
+#[allow(clippy::too_many_arguments)]
+fn index_movements(
+    txn: &mut rusqlite::Transaction,
+    ledger: &mut Ledger,
+    tx: Option<u32>,
+    msg: Option<u32>,
+    events: &[(String, HashMap<String, String>)],
+    labels: Vec<Label>,
+    kind: Option<&'static str>,
+) -> rusqlite::Result<()> {
+    let mut labels = Label::sorted(labels);
+
+    for movement in movements(ledger.block, events) {
+        let kind = match labels.iter().position(|label| label.matches(&movement)) {
+            Some(position) => labels.remove(position).kind,
+            None => kind.unwrap_or_else(|| default_kind(&movement)),
+        };
+        let sender = movement.sender.as_deref().unwrap_or(bank::MULTI);
+        let receiver = movement.receiver.as_deref().unwrap_or(bank::MULTI);
+        ledger.record(txn, tx, msg, kind, sender, receiver, &movement.coin)?;
+    }
+    Ok(())
+}
+
+/// Message the `idx` one is wrapped in, itself when it is not
+fn root(msgs: &[&MsgRow], idx: u32) -> u32 {
+    match msgs
+        .iter()
+        .find(|msg| msg.idx == idx)
+        .and_then(|msg| msg.parent)
+    {
+        Some(parent) if parent != idx => root(msgs, parent),
+        _ => idx,
+    }
+}
+
+/// Splits the events of a tx by message, each one starts with the `message` event naming
+/// its action. What comes before is emitted by the ante handler, fees included.
+fn split_by_msg(
+    events: Vec<(String, HashMap<String, String>)>,
+) -> Vec<Vec<(String, HashMap<String, String>)>> {
+    let mut split = vec![Vec::new()];
+    for event in events {
+        if event.0 == "message" && event.1.contains_key("action") {
+            split.push(Vec::new());
+        }
+        split.last_mut().unwrap().push(event);
+    }
+    split
+}
+
+fn index_tx(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    ledger: &mut Ledger,
+    tx: &TxRow,
+    msgs: &[&MsgRow],
+) -> rusqlite::Result<()> {
+    let mut split = split_by_msg(indexer::all_tx_events(txn, tx.block, tx.idx)?).into_iter();
+
+    let ante = split.next().unwrap_or_default();
+    let fee = Some(ledger_entry::FEE);
+    index_movements(txn, ledger, Some(tx.idx), None, &ante, vec![], fee)?;
+
+    // Failed txs only keep the events of the ante handler
+    let mut top: Vec<u32> = msgs
+        .iter()
+        .filter(|msg| msg.parent.is_none())
+        .map(|msg| msg.idx)
+        .collect();
+    top.sort_unstable();
+
+    for (i, events) in split.enumerate() {
+        let idx = top.get(i).copied();
+        let wrapped: Vec<&MsgRow> = msgs
+            .iter()
+            .filter(|msg| Some(root(msgs, msg.idx)) == idx)
+            .copied()
+            .collect();
+        let mut labeled = msg_labels(txn, registry, &wrapped)?;
+        labeled.extend(event_labels(ledger.block, &events));
+        index_movements(txn, ledger, Some(tx.idx), idx, &events, labeled, None)?;
+    }
+    Ok(())
+}
+
+fn index_block_phase(
+    txn: &mut rusqlite::Transaction,
+    ledger: &mut Ledger,
+    phase: model::Phase,
+) -> rusqlite::Result<()> {
+    let events = indexer::all_block_events(txn, ledger.block, phase)?;
+    let labeled = event_labels(ledger.block, &events);
+    index_movements(txn, ledger, None, None, &events, labeled, None)
+}
+
+/// Entries come from the bank events of the block, in the order they were emitted,
+/// messages only name them
+fn index_ledger_block(
+    txn: &mut rusqlite::Transaction,
+    args: &Args,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    let mut ledger = Ledger {
+        block: height,
+        idx: 0,
+    };
+    let msgs = tables::msg::by_block(txn, height)?;
+
+    index_block_phase(txn, &mut ledger, model::Phase::BeginBlock)?;
+
+    for tx in &tables::tx::by_block(txn, height)? {
+        let msgs: Vec<&MsgRow> = msgs.iter().filter(|msg| msg.tx == tx.idx).collect();
+        index_tx(txn, registry, &mut ledger, tx, &msgs)?;
+    }
+
+    index_block_phase(txn, &mut ledger, model::Phase::EndBlock)?;
+
+    if height.is_multiple_of(args.balance_checkpoint_interval) {
+        tables::balance::checkpoint(txn, height)?;
+    }
+    Ok(())
+}
+
+/// Seeds the balances with the imported genesis, once and before any block is indexed.
+/// Bonded tokens are part of the balance of the bonded pool, as they are once delegated.
+fn seed_genesis(args: &Args) -> rusqlite::Result<()> {
+    let mut conn = tables::schema::conn(&args.datadir)?;
+    let mut txn = conn.transaction()?;
+
+    let genesis = match tables::genesis::get(&mut txn)? {
+        Some(genesis) => genesis,
+        None => return Ok(()),
+    };
+    let indexed = tables::cursor::get(&mut txn, CURSOR)?.is_some();
+    let seeded = tables::balance::last_checkpoint(&mut txn, u32::MAX as u64)?.is_some();
+    if indexed || seeded {
+        return Ok(());
+    }
+
+    if args.from_block as u64 != genesis.height + 1 {
+        log::warn!(
+            "Seeding balances at genesis height {} but indexing from block {}",
+            genesis.height,
+            args.from_block
+        );
+    }
+
+    let mut balances = BTreeMap::<(String, String), i128>::new();
+    for balance in tables::genesis::balances(&mut txn)? {
+        *balances
+            .entry((balance.address, balance.denom))
+            .or_default() += balance.amount as i128;
+    }
+
+    for ((address, denom), amount) in &balances {
+        let row = &BalanceRow::new(genesis.height, address, denom, *amount);
+        tables::balance::set(&mut txn, row)?;
+        tables::balance::insert_checkpoint(&mut txn, row)?;
+    }
+
+    log::info!(
+        "Seeded {} balances from the genesis of {}",
+        balances.len(),
+        genesis.chain_id
+    );
+    txn.commit()
+}
+
+/// Removes the entries of `block` onward and takes their movements out of the balances
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    let mut deltas = BTreeMap::<(String, String), i128>::new();
+    for entry in ledger_entry::since(txn, block)? {
+        *deltas.entry((entry.address, entry.denom)).or_default() +=
+            entry.credit as i128 - entry.debit as i128;
+    }
+
+    for ((address, denom), delta) in &deltas {
+        let amount = tables::balance::get(txn, address, denom)?
+            .map(|it| it.amount)
+            .unwrap_or_default();
+        let row = &BalanceRow::new(block.saturating_sub(1), address, denom, amount - delta);
+        tables::balance::set(txn, row)?;
+    }
+
+    ledger_entry::delete_since(txn, block)?;
+    tables::balance::delete_checkpoints_since(txn, block)
+}
+
+pub async fn index_ledger(args: &Args, registry: &Registry) {
+    // Entries are only meaningful on top of the genesis balances, so seeding is retried
+    let mut backoff = indexer::DERIVED_BACKOFF;
+    while let Err(err) = seed_genesis(args) {
+        log::error!("Failed to seed the genesis balances : {}", err);
+        sleep(backoff).await;
+        backoff = std::cmp::min(backoff * 2, indexer::DERIVED_BACKOFF_MAX);
+    }
+
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_ledger_block(txn, args, registry, height)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn send(
+        txn: &mut rusqlite::Transaction,
+        block: u64,
+        sender: &str,
+        receiver: &str,
+        amount: u128,
+    ) {
+        let mut ledger = Ledger { block, idx: 0 };
+        let coin = &model::Coin {
+            denom: "uatom".to_string(),
+            amount,
+        };
+        let kind = ledger_entry::TRANSFER;
+        ledger
+            .record(txn, Some(0), Some(0), kind, sender, receiver, coin)
+            .unwrap();
+    }
+
+    fn balance(txn: &mut rusqlite::Transaction, address: &str) -> i128 {
+        tables::balance::get(txn, address, "uatom")
+            .unwrap()
+            .map_or(0, |it| it.amount)
+    }
+
+    fn checkpoints(txn: &mut rusqlite::Transaction, address: &str, block: u64) -> Vec<(u64, i128)> {
+        tables::balance::checkpoints_by_address(txn, address, block)
+            .unwrap()
+            .into_iter()
+            .map(|it| (it.block, it.amount))
+            .collect()
+    }
+
+    fn event(kind: &str, address: &str, amount: &str) -> (String, HashMap<String, String>) {
+        let key = if kind == "coin_spent" {
+            "spender"
+        } else {
+            "receiver"
+        };
+        let attributes = [(key, address), ("amount", amount)]
+            .map(|(key, value)| (key.to_string(), value.to_string()));
+        (kind.to_string(), HashMap::from(attributes))
+    }
+
+    fn moved(events: &[(String, HashMap<String, String>)]) -> Vec<(String, String, String)> {
+        movements(1, events)
+            .into_iter()
+            .map(|it| {
+                let side = |address: Option<String>| address.unwrap_or_default();
+                let coin = format!("{}{}", it.coin.amount, it.coin.denom);
+                (side(it.sender), side(it.receiver), coin)
+            })
+            .collect()
+    }
+
+    fn movement(sender: &str, receiver: &str, coin: &str) -> (String, String, String) {
+        (sender.to_string(), receiver.to_string(), coin.to_string())
+    }
+
+    #[test]
+    fn movements_pair_spends_and_receipts() {
+        let events = [
+            // A multi-send, then a voucher sent to the transfer module and burnt
+            event("coin_spent", "a", "30uatom,5ibc/X"),
+            event("coin_received", "b", "10uatom"),
+            event("coin_received", "c", "20uatom,5ibc/X"),
+            ("transfer".to_string(), HashMap::new()),
+            event("coin_spent", "b", "7ibc/Y"),
+            event("coin_received", "transfer", "7ibc/Y"),
+            event("coin_spent", "transfer", "7ibc/Y"),
+            // Minted then sent on
+            event("coin_received", "mint", "4uatom"),
+            event("coin_spent", "mint", "4uatom"),
+            event("coin_received", "d", "4uatom"),
+        ];
+
+        assert_eq!(
+            moved(&events),
+            vec![
+                movement("a", "b", "10uatom"),
+                movement("a", "c", "20uatom"),
+                movement("a", "c", "5ibc/X"),
+                movement("b", "transfer", "7ibc/Y"),
+                movement("", "mint", "4uatom"),
+                movement("mint", "d", "4uatom"),
+                movement("transfer", "", "7ibc/Y"),
+            ]
+        );
+    }
+
+    #[test]
+    fn checkpoints_hold_the_balances_changed_since_the_previous_one() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        tables::schema::create(&conn).unwrap();
+        let mut txn = conn.transaction().unwrap();
+
+        send(&mut txn, 10, "a", "b", 100);
+        tables::balance::checkpoint(&mut txn, 10).unwrap();
+        send(&mut txn, 20, "b", "c", 30);
+        tables::balance::checkpoint(&mut txn, 20).unwrap();
+
+        assert_eq!(checkpoints(&mut txn, "a", 20), vec![(10, -100)]);
+        assert_eq!(checkpoints(&mut txn, "b", 15), vec![(10, 100)]);
+        assert_eq!(checkpoints(&mut txn, "b", 20), vec![(20, 70)]);
+        assert_eq!(checkpoints(&mut txn, "c", 15), vec![]);
+        assert_eq!(checkpoints(&mut txn, "c", 25), vec![(20, 30)]);
+        assert_eq!(
+            tables::balance::last_checkpoint(&mut txn, 19).unwrap(),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn rewind_restores_the_balances_before_the_block() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        tables::schema::create(&conn).unwrap();
+        let mut txn = conn.transaction().unwrap();
+
+        send(&mut txn, 10, "a", "b", 100);
+        tables::balance::checkpoint(&mut txn, 10).unwrap();
+        send(&mut txn, 20, "b", "c", 30);
+        tables::balance::checkpoint(&mut txn, 20).unwrap();
+        send(&mut txn, 30, "a", "c", 5);
+
+        rewind(&mut txn, 20).unwrap();
+
+        assert_eq!(balance(&mut txn, "a"), -100);
+        assert_eq!(balance(&mut txn, "b"), 100);
+        assert_eq!(balance(&mut txn, "c"), 0);
+        assert!(ledger_entry::since(&mut txn, 20).unwrap().is_empty());
+        assert_eq!(ledger_entry::since(&mut txn, 0).unwrap().len(), 2);
+        assert_eq!(
+            tables::balance::last_checkpoint(&mut txn, 30).unwrap(),
+            Some(10)
+        );
+    }
+}
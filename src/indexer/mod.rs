@@ -0,0 +1,805 @@
+use futures::stream::{self, StreamExt};
+use rusqlite::{OptionalExtension, TransactionBehavior};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Duration, Instant};
+
+use crate::args::Args;
+use crate::client::{self, NewBlocks};
+use crate::decoder::{self, Registry};
+use crate::fetch;
+use crate::model;
+use crate::source::{self, BlockSource};
+use crate::tables;
+
+use crate::tables::address_msg::AddressMsgRow;
+use crate::tables::archive::{ArchiveBlockResultsRow, ArchiveTxRow};
+use crate::tables::block::BlockRow;
+use crate::tables::commit_signature::CommitSignatureRow;
+use crate::tables::event::{EventAttributeRow, EventRow};
+use crate::tables::msg::MsgRow;
+use crate::tables::tx::TxRow;
+use crate::tables::tx_fee::TxFeeRow;
+use crate::tables::tx_signer::TxSignerRow;
+use crate::tables::validator_set::ValidatorSetRow;
+
+pub mod authz;
+pub mod delegation;
+pub mod gov;
+pub mod ibc;
+pub mod ledger;
+pub mod reindex;
+pub mod rewards;
+pub mod transfers;
+pub mod validator;
+pub mod wasm;
+
+/// A subscription without any new block for this long is considered dead
+const TAIL_STALL: Duration = Duration::from_secs(60);
+
+/// Delay before subscribing again after a failed attempt
+const SUBSCRIBE_RETRY: Duration = Duration::from_secs(60);
+
+/// First delay before a derived indexer retries a failed block, doubled up to the maximum
+const DERIVED_BACKOFF: Duration = Duration::from_secs(1);
+const DERIVED_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Why a block could not be indexed
+#[derive(Debug)]
+pub enum Error {
+    /// The block could not be fetched or decoded from its source
+    Source(client::Error),
+    /// The indexed blocks could not be read or written
+    Store(rusqlite::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Source(err) => write!(f, "{}", err),
+            Error::Store(err) => write!(f, "Database error : {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    /// Decoding fails the same way on every attempt, retrying cannot get past it
+    fn is_fatal(&self) -> bool {
+        matches!(self, Error::Source(client::Error::Decode(_)))
+    }
+}
+
+impl From<client::Error> for Error {
+    fn from(err: client::Error) -> Self {
+        Error::Source(err)
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        Error::Store(err)
+    }
+}
+
+fn insert_events(
+    txn: &mut rusqlite::Transaction,
+    block: &model::Block,
+    phase: model::Phase,
+    tx: Option<&model::Tx>,
+    events: &[model::Event],
+) -> rusqlite::Result<()> {
+    for event in events {
+        let row = &EventRow::new(block, phase, tx, event);
+        tables::event::insert(txn, row)?;
+
+        for attribute in &event.attributes {
+            let row = &EventAttributeRow::new(row, attribute);
+            tables::event::insert_attribute(txn, row)?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_tx(
+    txn: &mut rusqlite::Transaction,
+    block: &model::Block,
+    tx: &model::Tx,
+) -> rusqlite::Result<()> {
+    let row = &TxRow::new(block, tx);
+    tables::tx::insert(txn, row)?;
+
+    for (i, coin) in tx.fee.iter().enumerate() {
+        let row = &TxFeeRow::new(block, tx, i as u32, coin);
+        tables::tx_fee::insert(txn, row)?;
+    }
+
+    for signer in &tx.signers {
+        let row = &TxSignerRow::new(block, tx, signer);
+        tables::tx_signer::insert(txn, row)?;
+    }
+    Ok(())
+}
+
+fn insert_msgs(
+    txn: &mut rusqlite::Transaction,
+    block: &model::Block,
+    tx: &model::Tx,
+) -> rusqlite::Result<()> {
+    for msg in &tx.msgs {
+        let row = &MsgRow::new(block, tx, msg);
+        tables::msg::insert(txn, row)?;
+
+        for address in &msg.addresses {
+            let row = AddressMsgRow::new(block, tx, msg, address);
+            tables::address_msg::insert(txn, &row)?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_commit(
+    txn: &mut rusqlite::Transaction,
+    validator_set: Option<&model::ValidatorSet>,
+    commit: &model::Commit,
+) -> rusqlite::Result<()> {
+    if let Some(set) = validator_set {
+        for validator in &set.validators {
+            tables::validator_set::insert(txn, &ValidatorSetRow::new(set, validator))?;
+        }
+    }
+
+    for sig in &commit.signatures {
+        tables::commit_signature::insert(txn, &CommitSignatureRow::new(commit, sig))?;
+    }
+    Ok(())
+}
+
+fn insert_block(
+    conn: &mut rusqlite::Connection,
+    block: &model::Block,
+    validator_set: Option<&model::ValidatorSet>,
+) -> rusqlite::Result<()> {
+    let mut txn = conn.transaction()?;
+
+    let row = &BlockRow::new(block);
+    tables::block::insert(&mut txn, row)?;
+
+    insert_events(
+        &mut txn,
+        block,
+        model::Phase::BeginBlock,
+        None,
+        &block.begin_block_events,
+    )?;
+
+    for tx in &block.txs {
+        insert_tx(&mut txn, block, tx)?;
+        insert_msgs(&mut txn, block, tx)?;
+        insert_events(&mut txn, block, model::Phase::Tx, Some(tx), &tx.events)?;
+    }
+
+    if let Some(archive) = &block.archive {
+        for (i, data) in archive.txs.iter().enumerate() {
+            let row = &ArchiveTxRow {
+                block: block.height,
+                idx: i as u32,
+                data: data.clone(),
+            };
+            tables::archive::insert_tx(&mut txn, row)?;
+        }
+
+        let row = &ArchiveBlockResultsRow {
+            block: block.height,
+            data: archive.results.clone(),
+        };
+        tables::archive::insert_block_results(&mut txn, row)?;
+    }
+
+    insert_events(
+        &mut txn,
+        block,
+        model::Phase::EndBlock,
+        None,
+        &block.end_block_events,
+    )?;
+
+    if let Some(commit) = &block.last_commit {
+        insert_commit(&mut txn, validator_set, commit)?;
+    }
+
+    txn.commit()
+}
+
+/// The stored snapshot no longer matches once any signing validator sits at another index
+fn is_stale(args: &Args, commit: &model::Commit, stored: &[ValidatorSetRow]) -> bool {
+    let due = stored
+        .first()
+        .map(|it| it.block + args.validator_set_interval <= commit.height)
+        .unwrap_or(true);
+
+    due || stored.len() != commit.signatures.len()
+        || commit
+            .signatures
+            .iter()
+            .any(|sig| match &sig.validator_address {
+                Some(address) => stored[sig.index as usize].address != *address,
+                None => false,
+            })
+}
+
+fn stored_validator_set(
+    conn: &mut rusqlite::Connection,
+    height: u64,
+) -> rusqlite::Result<Vec<ValidatorSetRow>> {
+    let mut txn = conn.transaction()?;
+    let stored = tables::validator_set::latest(&mut txn, height)?;
+    txn.commit()?;
+    Ok(stored)
+}
+
+/// Fetches the validator set that signed the commit when the stored one is stale,
+/// then fills in the address of absent signatures.
+async fn resolve_commit(
+    conn: &mut rusqlite::Connection,
+    source: &dyn BlockSource,
+    args: &Args,
+    commit: &mut model::Commit,
+) -> Result<Option<model::ValidatorSet>, Error> {
+    let stored = stored_validator_set(conn, commit.height)?;
+
+    let fetched = if is_stale(args, commit, &stored) {
+        Some(source.validator_set(commit.height).await?)
+    } else {
+        None
+    };
+
+    let addresses: Vec<&str> = match &fetched {
+        Some(set) => set
+            .validators
+            .iter()
+            .map(|it| it.address.as_str())
+            .collect(),
+        None => stored.iter().map(|it| it.address.as_str()).collect(),
+    };
+
+    for sig in &mut commit.signatures {
+        if sig.validator_address.is_none() {
+            let address = addresses.get(sig.index as usize);
+            sig.validator_address = address.map(|it| it.to_string());
+        }
+    }
+
+    Ok(fetched)
+}
+
+async fn index_history_block(
+    conn: &mut rusqlite::Connection,
+    source: &dyn BlockSource,
+    args: &Args,
+    mut block: model::Block,
+) -> Result<(), Error> {
+    let validator_set = match &mut block.last_commit {
+        Some(commit) => resolve_commit(conn, source, args, commit).await?,
+        None => None,
+    };
+
+    insert_block(conn, &block, validator_set.as_ref())?;
+    Ok(())
+}
+
+fn index_history_lower_bound(
+    conn: &mut rusqlite::Connection,
+    args: &Args,
+) -> rusqlite::Result<u64> {
+    let mut txn = conn.transaction()?;
+    let top = tables::block::top(&mut txn)
+        .map(|it| std::cmp::max(it.height + 1, args.from_block as u64))
+        .unwrap_or(args.from_block as u64);
+    txn.commit()?;
+    Ok(top)
+}
+
+async fn index_history_upper_bound(source: &dyn BlockSource, args: &Args) -> client::Result<u64> {
+    source
+        .latest_height()
+        .await
+        .map(|it| std::cmp::min(it, args.to_block as u64))
+}
+
+/// Up to `fetch_concurrency` blocks are fetched and decoded ahead, they are still written
+/// one at a time in height order. A block that cannot be fetched or stored stops the range,
+/// it is retried on the next round rather than skipped. Returns whether the whole range was
+/// indexed, or the error of a block that cannot be decoded.
+async fn index_history_range(
+    conn: &mut rusqlite::Connection,
+    source: &dyn BlockSource,
+    args: &Args,
+    registry: &Arc<Registry>,
+    lb: u64,
+    ub: u64,
+) -> Result<bool, Error> {
+    let mut blocks = stream::iter(lb..(ub + 1))
+        .map(|i| {
+            let registry = registry.clone();
+            async move { (i, source.block(registry, i, args.archive).await) }
+        })
+        .buffered(std::cmp::max(args.fetch_concurrency, 1));
+
+    while let Some((i, block)) = blocks.next().await {
+        let indexed = match block {
+            Ok(block) => index_history_block(conn, source, args, block).await,
+            Err(err) => Err(Error::Source(err)),
+        };
+        match indexed {
+            Err(err) if err.is_fatal() => {
+                log::error!("Cannot decode block {} : {}", i, err);
+                return Err(err);
+            }
+            Err(err) => {
+                log::error!("Failed to index block {} : {}", i, err);
+                return Ok(false);
+            }
+            Ok(()) => {}
+        }
+
+        if i % 1000 == 0 {
+            log::info!(
+                "Reached block : {} ({:?} throttled so far)",
+                i,
+                source.throttled()
+            )
+        } else {
+            log::debug!("Reached block : {}", i)
+        }
+    }
+    Ok(true)
+}
+
+/// Indexes up to each height announced by the subscription, until it drops or stalls.
+/// Heights missed in between are caught up by the range, so the order is kept.
+async fn index_history_tail(
+    conn: &mut rusqlite::Connection,
+    source: &dyn BlockSource,
+    args: &Args,
+    registry: &Arc<Registry>,
+    mut blocks: NewBlocks,
+) -> Result<(), Error> {
+    log::info!("Following new blocks on {}", blocks.url());
+
+    loop {
+        let height = match timeout(TAIL_STALL, blocks.next()).await {
+            Ok(Some(height)) => std::cmp::min(height, args.to_block as u64),
+            Ok(None) => {
+                log::warn!("Subscription on {} closed", blocks.url());
+                return Ok(());
+            }
+            Err(_) => {
+                log::warn!("No new block for {:?}, resubscribing", TAIL_STALL);
+                return Ok(());
+            }
+        };
+
+        let lb = match index_history_lower_bound(conn, args) {
+            Ok(lb) => lb,
+            Err(err) => {
+                log::error!("Failed to read the latest indexed block : {}", err);
+                return Ok(());
+            }
+        };
+        if lb > height {
+            if height >= args.to_block as u64 {
+                return Ok(());
+            }
+            continue;
+        }
+        if !index_history_range(conn, source, args, registry, lb, height).await? {
+            return Ok(());
+        }
+    }
+}
+
+/// Catches up by ranges, then follows new blocks over the WebSocket of the RPC endpoint.
+/// Whenever the WebSocket is unavailable, the latest block is polled every second instead.
+/// Only returns when the database or the source cannot be opened, or a block cannot be decoded.
+pub async fn index_history(args: &Args, registry: Arc<Registry>) -> Result<(), Error> {
+    let mut conn = tables::schema::conn(&args.datadir)?;
+    let source = source::open(args)?;
+    let source = source.as_ref();
+    let mut subscribe_after = Instant::now();
+
+    loop {
+        let lb = match index_history_lower_bound(&mut conn, args) {
+            Ok(lb) => lb,
+            Err(err) => {
+                log::error!("Failed to read the latest indexed block : {}", err);
+                sleep(Duration::from_millis(1000)).await;
+                continue;
+            }
+        };
+        let ub = match index_history_upper_bound(source, args).await {
+            Ok(ub) => ub,
+            Err(err) => {
+                log::error!("Failed to fetch the latest block : {}", err);
+                sleep(Duration::from_millis(1000)).await;
+                continue;
+            }
+        };
+
+        log::info!("Considering range : {} -> {}", lb, ub);
+
+        let complete = index_history_range(&mut conn, source, args, &registry, lb, ub).await?;
+        let following = complete && !args.poll && ub < args.to_block as u64;
+        if following && Instant::now() >= subscribe_after {
+            match source.new_blocks().await {
+                None => {}
+                Some(Ok(blocks)) => {
+                    index_history_tail(&mut conn, source, args, &registry, blocks).await?
+                }
+                Some(Err(err)) => {
+                    log::warn!(
+                        "Cannot follow new blocks, polling for {:?} : {}",
+                        SUBSCRIBE_RETRY,
+                        err
+                    );
+                    subscribe_after = Instant::now() + SUBSCRIBE_RETRY;
+                }
+            }
+        }
+        sleep(Duration::from_millis(1000)).await
+    }
+}
+
+fn index_derived_lower_bound(
+    conn: &mut rusqlite::Connection,
+    args: &Args,
+    cursor: &str,
+) -> rusqlite::Result<u64> {
+    let mut txn = conn.transaction()?;
+    let top = tables::cursor::get(&mut txn, cursor)?
+        .map(|it| std::cmp::max(it + 1, args.from_block as u64))
+        .unwrap_or(args.from_block as u64);
+    txn.commit()?;
+    Ok(top)
+}
+
+fn index_derived_upper_bound(
+    conn: &mut rusqlite::Connection,
+    args: &Args,
+) -> rusqlite::Result<u64> {
+    let mut txn = conn.transaction()?;
+    let top = tables::block::top(&mut txn)
+        .optional()?
+        .map(|it| std::cmp::min(it.height, args.to_block as u64))
+        .unwrap_or(0);
+    txn.commit()?;
+    Ok(top)
+}
+
+fn index_derived_block<F>(
+    conn: &mut rusqlite::Connection,
+    cursor: &str,
+    height: u64,
+    index_block: &mut F,
+) -> rusqlite::Result<()>
+where
+    F: FnMut(&mut rusqlite::Transaction, u64) -> rusqlite::Result<()>,
+{
+    // Immediate so that the read-then-write does not deadlock against index_history
+    let mut txn = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    index_block(&mut txn, height)?;
+    tables::cursor::set(&mut txn, cursor, height)?;
+    txn.commit()
+}
+
+/// Runs a derived indexer over the blocks stored by index_history.
+/// Progress is tracked under its own cursor and each block is committed atomically.
+/// A block that fails stops the range, it is retried after a backoff rather than skipped.
+async fn index_derived<F>(args: &Args, cursor: &str, mut index_block: F)
+where
+    F: FnMut(&mut rusqlite::Transaction, u64) -> rusqlite::Result<()>,
+{
+    let mut backoff = DERIVED_BACKOFF;
+    let mut conn = loop {
+        match tables::schema::conn(&args.datadir) {
+            Ok(conn) => break conn,
+            Err(err) => {
+                log::error!("Failed to open the database for {} : {}", cursor, err);
+                sleep(backoff).await;
+                backoff = std::cmp::min(backoff * 2, DERIVED_BACKOFF_MAX);
+            }
+        }
+    };
+
+    loop {
+        let bounds = index_derived_lower_bound(&mut conn, args, cursor)
+            .and_then(|lb| Ok((lb, index_derived_upper_bound(&mut conn, args)?)));
+        let (lb, ub) = match bounds {
+            Ok(bounds) => bounds,
+            Err(err) => {
+                log::error!("Failed to read the {} range : {}", cursor, err);
+                sleep(backoff).await;
+                backoff = std::cmp::min(backoff * 2, DERIVED_BACKOFF_MAX);
+                continue;
+            }
+        };
+
+        log::debug!("Considering {} range : {} -> {}", cursor, lb, ub);
+
+        let mut failed = false;
+        for i in lb..(ub + 1) {
+            if let Err(err) = index_derived_block(&mut conn, cursor, i, &mut index_block) {
+                log::error!("Failed to index {} block {} : {}", cursor, i, err);
+                failed = true;
+                break;
+            }
+
+            if i % 1000 == 0 {
+                log::info!("Reached {} block : {}", cursor, i)
+            } else {
+                log::debug!("Reached {} block : {}", cursor, i)
+            }
+        }
+
+        if failed {
+            sleep(backoff).await;
+            backoff = std::cmp::min(backoff * 2, DERIVED_BACKOFF_MAX);
+        } else {
+            backoff = DERIVED_BACKOFF;
+            sleep(Duration::from_millis(1000)).await
+        }
+    }
+}
+
+/// Pairs each event with its attributes, keeping the order of `events`
+fn with_attributes(
+    events: Vec<EventRow>,
+    attributes: Vec<EventAttributeRow>,
+) -> Vec<(String, HashMap<String, String>)> {
+    let positions: HashMap<u32, usize> = events
+        .iter()
+        .enumerate()
+        .map(|(position, event)| (event.idx, position))
+        .collect();
+    let mut events: Vec<(String, HashMap<String, String>)> = events
+        .into_iter()
+        .map(|event| (event.kind, HashMap::new()))
+        .collect();
+
+    for attr in attributes {
+        if let Some(position) = positions.get(&attr.event) {
+            events[*position].1.insert(attr.key, attr.value);
+        }
+    }
+    events
+}
+
+/// Every event emitted by a tx, as its type and attributes, in emission order
+fn all_tx_events(
+    txn: &mut rusqlite::Transaction,
+    block: u64,
+    tx: u32,
+) -> rusqlite::Result<Vec<(String, HashMap<String, String>)>> {
+    let events = tables::event::by_tx(txn, block, tx)?;
+    let attributes = tables::event::attributes_by_tx(txn, block, tx)?;
+    Ok(with_attributes(events, attributes))
+}
+
+/// Every event emitted at begin or end block, as its type and attributes, in emission order
+fn all_block_events(
+    txn: &mut rusqlite::Transaction,
+    block: u64,
+    phase: model::Phase,
+) -> rusqlite::Result<Vec<(String, HashMap<String, String>)>> {
+    let phase = phase.as_str();
+    let events = tables::event::by_block(txn, block)?
+        .into_iter()
+        .filter(|event| event.phase == phase && event.tx.is_none())
+        .collect();
+    let attributes = tables::event::attributes_by_block(txn, block)?
+        .into_iter()
+        .filter(|attr| attr.phase == phase && attr.tx.is_none())
+        .collect();
+    Ok(with_attributes(events, attributes))
+}
+
+fn of_kind(
+    events: Vec<(String, HashMap<String, String>)>,
+    kind: &str,
+) -> Vec<HashMap<String, String>> {
+    events
+        .into_iter()
+        .filter(|(it, _)| it == kind)
+        .map(|(_, attributes)| attributes)
+        .collect()
+}
+
+/// Attributes of the events of a given type emitted by a tx, in emission order
+fn tx_events(
+    txn: &mut rusqlite::Transaction,
+    block: u64,
+    tx: u32,
+    kind: &str,
+) -> rusqlite::Result<Vec<HashMap<String, String>>> {
+    Ok(of_kind(all_tx_events(txn, block, tx)?, kind))
+}
+
+/// What was decoded from a message, or `None` once the failure is logged.
+/// Decoding is deterministic, failing the block would only fail it again on every round.
+pub fn decoded<T>(msg: &MsgRow, decoded: cosmrs::Result<T>) -> Option<T> {
+    match decoded {
+        Ok(decoded) => Some(decoded),
+        Err(err) => {
+            log::warn!(
+                "Skipping message {}/{}/{} ({}) : {}",
+                msg.block,
+                msg.tx,
+                msg.idx,
+                msg.tag,
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Actions of a message, none when it cannot be decoded
+fn msg_actions(registry: &Registry, msg: &MsgRow) -> Vec<model::Action> {
+    decoded(msg, registry.actions(&msg.tag, &msg.data)).unwrap_or_default()
+}
+
+/// Contract addresses of a tx in instantiation order, older wasmd omits the leading underscore
+fn instantiated_addresses(
+    txn: &mut rusqlite::Transaction,
+    height: u64,
+    tx: u32,
+) -> rusqlite::Result<Vec<String>> {
+    Ok(tx_events(txn, height, tx, "instantiate")?
+        .into_iter()
+        .filter_map(|mut event| {
+            event
+                .remove("_contract_address")
+                .or_else(|| event.remove("contract_address"))
+        })
+        .collect())
+}
+
+/// Address of the contract instantiated by a message, matched to the `instantiate` events
+/// of its tx by the number of instantiations that precede it
+fn contract_address(
+    txn: &mut rusqlite::Transaction,
+    msg: &MsgRow,
+) -> rusqlite::Result<Option<String>> {
+    let position = tables::msg::by_block(txn, msg.block)?
+        .iter()
+        .filter(|it| {
+            it.tx == msg.tx && it.idx < msg.idx && it.tag == decoder::wasm::INSTANTIATE_TYPE_URL
+        })
+        .count();
+    Ok(instantiated_addresses(txn, msg.block, msg.tx)?
+        .into_iter()
+        .nth(position))
+}
+
+/// Transfers of a message, none when it cannot be decoded.
+/// Funds sent to an instantiated contract get its address from the events of the tx.
+fn msg_transfers(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    msg: &MsgRow,
+) -> rusqlite::Result<Vec<model::Transfer>> {
+    let mut transfers = decoded(msg, fetch::msg_transfers(registry, msg)).unwrap_or_default();
+    if !transfers
+        .iter()
+        .any(|it| it.receiver == decoder::wasm::INSTANTIATED)
+    {
+        return Ok(transfers);
+    }
+
+    match contract_address(txn, msg)? {
+        Some(address) => {
+            for transfer in &mut transfers {
+                if transfer.receiver == decoder::wasm::INSTANTIATED {
+                    transfer.receiver = address.clone();
+                }
+            }
+        }
+        None => {
+            log::warn!(
+                "No contract address for message {}/{}/{}",
+                msg.block,
+                msg.tx,
+                msg.idx
+            );
+            transfers.retain(|it| it.receiver != decoder::wasm::INSTANTIATED);
+        }
+    }
+    Ok(transfers)
+}
+
+/// Messages of the block whose tx was successfully delivered
+fn successful_msgs(txn: &mut rusqlite::Transaction, height: u64) -> rusqlite::Result<Vec<MsgRow>> {
+    let failed: HashSet<u32> = tables::tx::by_block(txn, height)?
+        .into_iter()
+        .filter(|tx| tx.code != 0)
+        .map(|tx| tx.idx)
+        .collect();
+
+    Ok(tables::msg::by_block(txn, height)?
+        .into_iter()
+        .filter(|msg| !failed.contains(&msg.tx))
+        .collect())
+}
+
+type Rewind = fn(&mut rusqlite::Transaction, u64) -> rusqlite::Result<()>;
+
+/// Every derived indexer, along with what removes its rows from a height onward
+const DERIVED: [(&str, Rewind); 9] = [
+    (transfers::CURSOR, transfers::rewind),
+    (gov::CURSOR, gov::rewind),
+    (ibc::CURSOR, ibc::rewind),
+    (authz::CURSOR, authz::rewind),
+    (wasm::CURSOR, wasm::rewind),
+    (validator::CURSOR, validator::rewind),
+    (delegation::CURSOR, delegation::rewind),
+    (rewards::CURSOR, rewards::rewind),
+    (ledger::CURSOR, ledger::rewind),
+];
+
+/// Rewinds the derived indexers that went past `block`, they catch up again from there
+pub fn invalidate_derived(conn: &mut rusqlite::Connection, block: u64) -> rusqlite::Result<()> {
+    let mut txn = conn.transaction()?;
+    for (cursor, rewind) in DERIVED {
+        let indexed = tables::cursor::get(&mut txn, cursor)?;
+        if indexed.is_some_and(|it| it >= block) {
+            rewind(&mut txn, block)?;
+            tables::cursor::set(&mut txn, cursor, block.saturating_sub(1))?;
+            log::info!("Rewound {} to block {}", cursor, block.saturating_sub(1));
+        }
+    }
+    txn.commit()
+}
+
+/// Spawns the history indexer along with every derived indexer
+pub fn spawn(args: Arc<Args>, registry: Arc<Registry>) -> Vec<JoinHandle<()>> {
+    let (a, r) = (args.clone(), registry.clone());
+    let history = tokio::spawn(async move {
+        if let Err(err) = index_history(&a, r).await {
+            log::error!("Stopped indexing history : {}", err);
+            std::process::exit(1);
+        }
+    });
+
+    let (a, r) = (args.clone(), registry.clone());
+    let transfers = tokio::spawn(async move { transfers::index_transfers(&a, &r).await });
+
+    let (a, r) = (args.clone(), registry.clone());
+    let gov = tokio::spawn(async move { gov::index_gov(&a, &r).await });
+
+    let (a, r) = (args.clone(), registry.clone());
+    let ibc = tokio::spawn(async move { ibc::index_ibc(&a, &r).await });
+
+    let (a, r) = (args.clone(), registry.clone());
+    let authz = tokio::spawn(async move { authz::index_authz(&a, &r).await });
+
+    let (a, r) = (args.clone(), registry.clone());
+    let wasm = tokio::spawn(async move { wasm::index_wasm(&a, &r).await });
+
+    let (a, r) = (args.clone(), registry.clone());
+    let validator = tokio::spawn(async move { validator::index_validator(&a, &r).await });
+
+    let (a, r) = (args.clone(), registry.clone());
+    let delegation = tokio::spawn(async move { delegation::index_delegation(&a, &r).await });
+
+    let (a, r) = (args.clone(), registry.clone());
+    let rewards = tokio::spawn(async move { rewards::index_rewards(&a, &r).await });
+
+    let (a, r) = (args, registry);
+    let ledger = tokio::spawn(async move { ledger::index_ledger(&a, &r).await });
+
+    vec![
+        history, transfers, gov, ibc, authz, wasm, validator, delegation, rewards, ledger,
+    ]
+}
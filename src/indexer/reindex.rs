@@ -0,0 +1,183 @@
+use rusqlite::OptionalExtension;
+
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::fetch;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::address_msg::AddressMsgRow;
+use crate::tables::block::BlockRow;
+
+/// `tx`, `tx_fee` and `tx_signer`, decoded again from the archive
+pub const TX: &str = "tx";
+/// `msg` and `address_msg`, decoded again from the archive
+pub const MSG: &str = "msg";
+/// `address_msg` alone, decoded again from the stored messages
+pub const ADDRESS_MSG: &str = "address_msg";
+/// `event` and `event_attribute`, decoded again from the archive
+pub const EVENT: &str = "event";
+
+const TABLES: [&str; 4] = [TX, MSG, ADDRESS_MSG, EVENT];
+
+fn err(msg: String) -> cosmrs::ErrorReport {
+    cosmrs::ErrorReport::msg(msg)
+}
+
+/// The indexed block with its txs and events decoded again from the archive
+fn archived_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    row: BlockRow,
+) -> cosmrs::Result<model::Block> {
+    let results = tables::archive::block_results_by_block(txn, row.height)?.ok_or_else(|| {
+        err(format!(
+            "Block {} is not archived, it must be indexed with --archive",
+            row.height
+        ))
+    })?;
+    let results = fetch::archived_block_results(&results.data)?;
+
+    let txs = tables::archive::txs_by_block(txn, row.height)?
+        .iter()
+        .map(|tx| fetch::archived_tx(&tx.data))
+        .collect::<cosmrs::Result<_>>()?;
+
+    Ok(model::Block {
+        hash: row.hash,
+        height: row.height,
+        time: row.time,
+        proposer: row.proposer,
+        txs: fetch::archived_txs_to_model(registry, txs, &results)?,
+        begin_block_events: fetch::events_to_model(
+            results.begin_block_events.as_deref().unwrap_or_default(),
+        ),
+        end_block_events: fetch::events_to_model(
+            results.end_block_events.as_deref().unwrap_or_default(),
+        ),
+        last_commit: None,
+        archive: None,
+    })
+}
+
+/// Addresses of the stored messages, with the decoders of the current registry.
+/// As when fetched, a message that cannot be decoded is left without addresses
+fn reindex_address_msgs(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> cosmrs::Result<()> {
+    tables::address_msg::delete_by_block(txn, height)?;
+    for msg in tables::msg::by_block(txn, height)? {
+        let addresses = indexer::decoded(&msg, registry.addresses(&msg.tag, &msg.data));
+        for address in addresses.unwrap_or_default() {
+            let row = &AddressMsgRow {
+                address: address.address,
+                role: address.role,
+                block: msg.block,
+                tx: msg.tx,
+                msg: msg.idx,
+            };
+            tables::address_msg::insert(txn, row)?;
+        }
+    }
+    Ok(())
+}
+
+fn reindex_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    targets: &[&str],
+    row: BlockRow,
+) -> cosmrs::Result<()> {
+    let height = row.height;
+    if targets == [ADDRESS_MSG] {
+        return reindex_address_msgs(txn, registry, height);
+    }
+
+    let block = archived_block(txn, registry, row)?;
+
+    if targets.contains(&TX) {
+        tables::tx::delete_by_block(txn, height)?;
+        tables::tx_fee::delete_by_block(txn, height)?;
+        tables::tx_signer::delete_by_block(txn, height)?;
+        for tx in &block.txs {
+            indexer::insert_tx(txn, &block, tx)?;
+        }
+    }
+
+    if targets.contains(&MSG) {
+        tables::msg::delete_by_block(txn, height)?;
+        tables::address_msg::delete_by_block(txn, height)?;
+        for tx in &block.txs {
+            indexer::insert_msgs(txn, &block, tx)?;
+        }
+    } else if targets.contains(&ADDRESS_MSG) {
+        reindex_address_msgs(txn, registry, height)?;
+    }
+
+    if targets.contains(&EVENT) {
+        tables::event::delete_by_block(txn, height)?;
+        let (begin, end) = (model::Phase::BeginBlock, model::Phase::EndBlock);
+        indexer::insert_events(txn, &block, begin, None, &block.begin_block_events)?;
+        for tx in &block.txs {
+            indexer::insert_events(txn, &block, model::Phase::Tx, Some(tx), &tx.events)?;
+        }
+        indexer::insert_events(txn, &block, end, None, &block.end_block_events)?;
+    }
+    Ok(())
+}
+
+/// Rebuilds `reindex_tables` for `reindex_from..=reindex_to`, one transaction per block, with
+/// the decoders of the current registry. Derived indexers are rewound to `reindex_from` first
+/// so that they catch up on the rebuilt rows once indexing resumes.
+pub fn reindex(args: &Args, registry: &Registry) -> cosmrs::Result<()> {
+    let from = args
+        .reindex_from
+        .ok_or_else(|| err("Missing --reindex-from".to_string()))?;
+
+    let mut targets: Vec<&str> = Vec::new();
+    for table in &args.reindex_tables {
+        let table = TABLES
+            .iter()
+            .find(|it| *it == table)
+            .ok_or_else(|| err(format!("Cannot reindex {}, only {:?}", table, TABLES)))?;
+        targets.push(table);
+    }
+    // `--reindex-tables address_msg,address_msg` still only needs the stored messages
+    targets.sort_unstable();
+    targets.dedup();
+
+    let mut conn = tables::schema::conn(&args.datadir)?;
+    let mut txn = conn.transaction()?;
+    let top = tables::block::top(&mut txn).optional()?.map(|it| it.height);
+    txn.commit()?;
+    let to = match (args.reindex_to, top) {
+        (Some(to), Some(top)) => std::cmp::min(to, top),
+        (None, Some(top)) => top,
+        (_, None) => return Err(err("No block indexed yet".to_string())),
+    };
+
+    log::info!("Reindexing {:?} : {} -> {}", targets, from, to);
+    indexer::invalidate_derived(&mut conn, from)?;
+
+    let mut count = 0;
+    for i in from..(to + 1) {
+        let mut txn = conn.transaction()?;
+        if let Some(row) = tables::block::by_height(&mut txn, i).optional()? {
+            reindex_block(&mut txn, registry, &targets, row)?;
+            count += 1;
+        }
+        txn.commit()?;
+
+        if i % 1000 == 0 {
+            log::info!("Reindexed block : {}", i)
+        } else {
+            log::debug!("Reindexed block : {}", i)
+        }
+    }
+
+    log::info!("Reindexed {} blocks : {} -> {}", count, from, to);
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::fetch;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::reward_withdrawal::RewardWithdrawalRow;
+
+pub const CURSOR: &str = "rewards";
+
+pub fn event_kind(kind: model::WithdrawalKind) -> &'static str {
+    match kind {
+        model::WithdrawalKind::Reward => "withdraw_rewards",
+        model::WithdrawalKind::Commission => "withdraw_commission",
+    }
+}
+
+/// Picks the first unconsumed event of the withdrawal, commission events carry no validator
+pub fn take_event(
+    events: &mut Vec<HashMap<String, String>>,
+    withdrawal: &model::Withdrawal,
+) -> Option<HashMap<String, String>> {
+    let position = events
+        .iter()
+        .position(|event| match event.get("validator") {
+            Some(validator) => validator == &withdrawal.validator,
+            None => true,
+        })?;
+    Some(events.remove(position))
+}
+
+fn index_rewards_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    let mut withdraw_events = HashMap::<(u32, &str), Vec<HashMap<String, String>>>::new();
+
+    for msg in &indexer::successful_msgs(txn, height)? {
+        for action in indexer::msg_actions(registry, msg) {
+            let withdrawal = match action {
+                model::Action::Withdraw(withdrawal) => withdrawal,
+                _ => continue,
+            };
+
+            let kind = event_kind(withdrawal.kind);
+            let events = match withdraw_events.entry((msg.tx, kind)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(indexer::tx_events(txn, height, msg.tx, kind)?)
+                }
+            };
+
+            match take_event(events, &withdrawal) {
+                Some(event) => {
+                    let amount = event.get("amount").map(String::as_str).unwrap_or_default();
+                    let coins = indexer::decoded(msg, fetch::parse_coins(amount));
+                    for (i, coin) in coins.unwrap_or_default().iter().enumerate() {
+                        let row = &RewardWithdrawalRow::new(msg, i as u32, &withdrawal, coin);
+                        tables::reward_withdrawal::insert(txn, row)?;
+                    }
+                }
+                None => log::warn!(
+                    "No {} event for message {}/{}/{}",
+                    kind,
+                    msg.block,
+                    msg.tx,
+                    msg.idx
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Removes what was derived from `block` onward
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    tables::reward_withdrawal::delete_since(txn, block)
+}
+
+pub async fn index_rewards(args: &Args, registry: &Registry) {
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_rewards_block(txn, registry, height)
+    })
+    .await
+}
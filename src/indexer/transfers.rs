@@ -0,0 +1,34 @@
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::indexer;
+use crate::tables;
+
+use crate::tables::msg_transfer::MsgTransferRow;
+
+pub const CURSOR: &str = "transfers";
+
+fn index_transfers_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    for msg in &indexer::successful_msgs(txn, height)? {
+        for transfer in &indexer::msg_transfers(txn, registry, msg)? {
+            let row = &MsgTransferRow::new(msg, transfer);
+            tables::msg_transfer::insert(txn, row)?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes what was derived from `block` onward
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    tables::msg_transfer::delete_since(txn, block)
+}
+
+pub async fn index_transfers(args: &Args, registry: &Registry) {
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_transfers_block(txn, registry, height)
+    })
+    .await
+}
@@ -0,0 +1,176 @@
+use rusqlite::OptionalExtension;
+
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::genesis::GenesisValidatorRow;
+use crate::tables::validator::ValidatorRow;
+use crate::tables::validator_history::{self as validator_history, ValidatorHistoryRow};
+
+pub const CURSOR: &str = "validator";
+
+fn index_validator_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    for msg in &indexer::successful_msgs(txn, height)? {
+        for action in indexer::msg_actions(registry, msg) {
+            let (kind, validator) = match action {
+                model::Action::CreateValidator(validator) => (validator_history::CREATE, validator),
+                model::Action::EditValidator(validator) => (validator_history::EDIT, validator),
+                _ => continue,
+            };
+
+            let created = kind == validator_history::CREATE;
+            tables::validator::upsert(txn, &ValidatorRow::new(height, created, &validator))?;
+            let row = &ValidatorHistoryRow::new(msg, kind, &validator);
+            tables::validator_history::insert(txn, row)?;
+        }
+    }
+    Ok(())
+}
+
+/// The validator as of before its edits from `block` onward, each field as last set by its
+/// earlier history or else by the genesis. Fields the genesis does not keep become unknown.
+fn restored(
+    current: ValidatorRow,
+    history: &[ValidatorHistoryRow],
+    genesis: Option<&GenesisValidatorRow>,
+    genesis_height: Option<u64>,
+) -> ValidatorRow {
+    let latest = |field: fn(&ValidatorHistoryRow) -> &Option<String>| {
+        history.iter().rev().find_map(|it| field(it).clone())
+    };
+
+    ValidatorRow {
+        moniker: latest(|it| &it.moniker).or_else(|| genesis.map(|it| it.moniker.clone())),
+        identity: latest(|it| &it.identity),
+        website: latest(|it| &it.website),
+        security_contact: latest(|it| &it.security_contact),
+        details: latest(|it| &it.details),
+        commission_rate: latest(|it| &it.commission_rate)
+            .or_else(|| genesis.map(|it| it.commission_rate.clone())),
+        min_self_delegation: latest(|it| &it.min_self_delegation),
+        updated: history
+            .last()
+            .map(|it| it.block)
+            .or(genesis_height.filter(|_| genesis.is_some()))
+            .unwrap_or(current.updated),
+        ..current
+    }
+}
+
+/// Removes what was derived from `block` onward, validators edited since are put back
+/// as they were before. Those only known from these edits are removed altogether.
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    tables::validator::delete_created_since(txn, block)?;
+
+    let genesis_height = tables::genesis::get(txn)?.map(|it| it.height);
+    for address in validator_history::operators_since(txn, block)? {
+        let current = match tables::validator::by_operator_address(txn, &address).optional()? {
+            Some(current) => current,
+            None => continue,
+        };
+        let history: Vec<ValidatorHistoryRow> =
+            validator_history::by_operator_address(txn, &address)?
+                .into_iter()
+                .filter(|it| it.block < block)
+                .collect();
+        let genesis = tables::genesis::validator(txn, &address)?;
+
+        if history.is_empty() && genesis.is_none() {
+            tables::validator::delete(txn, &address)?;
+        } else {
+            let row = &restored(current, &history, genesis.as_ref(), genesis_height);
+            tables::validator::replace(txn, row)?;
+        }
+    }
+
+    validator_history::delete_since(txn, block)
+}
+
+pub async fn index_validator(args: &Args, registry: &Registry) {
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_validator_block(txn, registry, height)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::msg::MsgRow;
+
+    fn validator(moniker: Option<&str>, rate: Option<&str>) -> model::Validator {
+        model::Validator {
+            operator_address: "cosmosvaloper1a".to_string(),
+            consensus_pubkey: None,
+            consensus_address: None,
+            moniker: moniker.map(str::to_string),
+            identity: None,
+            website: None,
+            security_contact: None,
+            details: None,
+            commission_rate: rate.map(str::to_string),
+            commission_max_rate: None,
+            commission_max_change_rate: None,
+            min_self_delegation: None,
+        }
+    }
+
+    fn index(txn: &mut rusqlite::Transaction, block: u64, kind: &str, validator: model::Validator) {
+        let msg = &MsgRow {
+            block,
+            tx: 0,
+            idx: 0,
+            parent: None,
+            tag: String::new(),
+            data: vec![],
+        };
+        let created = kind == validator_history::CREATE;
+        let row = &ValidatorRow::new(block, created, &validator);
+        tables::validator::upsert(txn, row).unwrap();
+        let row = &ValidatorHistoryRow::new(msg, kind, &validator);
+        tables::validator_history::insert(txn, row).unwrap();
+    }
+
+    #[test]
+    fn rewind_restores_earlier_edits() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        tables::schema::create(&conn).unwrap();
+        let mut txn = conn.transaction().unwrap();
+
+        index(
+            &mut txn,
+            10,
+            validator_history::CREATE,
+            validator(Some("a"), Some("0.1")),
+        );
+        index(
+            &mut txn,
+            20,
+            validator_history::EDIT,
+            validator(Some("b"), None),
+        );
+        index(
+            &mut txn,
+            30,
+            validator_history::EDIT,
+            validator(Some("c"), Some("0.2")),
+        );
+
+        rewind(&mut txn, 30).unwrap();
+        let row = tables::validator::by_operator_address(&mut txn, "cosmosvaloper1a").unwrap();
+        assert_eq!(row.moniker.as_deref(), Some("b"));
+        assert_eq!(row.commission_rate.as_deref(), Some("0.1"));
+        assert_eq!((row.created, row.updated), (Some(10), 20));
+
+        rewind(&mut txn, 10).unwrap();
+        let row = tables::validator::by_operator_address(&mut txn, "cosmosvaloper1a");
+        assert!(row.optional().unwrap().is_none());
+    }
+}
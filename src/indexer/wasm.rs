@@ -0,0 +1,171 @@
+use crate::args::Args;
+use crate::decoder::Registry;
+use crate::indexer;
+use crate::model;
+use crate::tables;
+
+use crate::tables::contract::ContractRow;
+use crate::tables::contract_execution::ContractExecutionRow;
+use crate::tables::contract_history::{self as contract_history, ContractHistoryRow};
+
+pub const CURSOR: &str = "wasm";
+
+fn index_wasm_block(
+    txn: &mut rusqlite::Transaction,
+    registry: &Registry,
+    height: u64,
+) -> rusqlite::Result<()> {
+    for msg in &indexer::successful_msgs(txn, height)? {
+        for action in indexer::msg_actions(registry, msg) {
+            match action {
+                model::Action::Instantiate(instantiate) => {
+                    // Contract addresses are derived on execution, they come from the events
+                    match indexer::contract_address(txn, msg)? {
+                        Some(address) => {
+                            let row = &ContractRow::new(msg, &address, &instantiate);
+                            tables::contract::insert(txn, row)?;
+                        }
+                        None => log::warn!(
+                            "No contract address for message {}/{}/{}",
+                            msg.block,
+                            msg.tx,
+                            msg.idx
+                        ),
+                    }
+                }
+                model::Action::Execute(execute) => {
+                    let row = &ContractExecutionRow::new(msg, &execute);
+                    tables::contract_execution::insert(txn, row)?;
+                }
+                // Contracts instantiated before indexing started are unknown, there is nothing to update
+                model::Action::Migrate(migrate) => {
+                    if let Some(previous) = tables::contract::get(txn, &migrate.contract)? {
+                        let admin = previous.admin.clone();
+                        let kind = contract_history::MIGRATE;
+                        let row =
+                            &ContractHistoryRow::new(msg, kind, &previous, migrate.code_id, admin);
+                        change(txn, row)?;
+                    }
+                }
+                model::Action::UpdateAdmin(update) => {
+                    if let Some(previous) = tables::contract::get(txn, &update.contract)? {
+                        let kind = contract_history::UPDATE_ADMIN;
+                        let row = &ContractHistoryRow::new(
+                            msg,
+                            kind,
+                            &previous,
+                            previous.code_id,
+                            update.admin,
+                        );
+                        change(txn, row)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Applies a migrate or admin change, keeping the replaced values so `rewind` can restore them
+fn change(txn: &mut rusqlite::Transaction, row: &ContractHistoryRow) -> rusqlite::Result<()> {
+    tables::contract::set_code_id(txn, &row.address, row.code_id)?;
+    tables::contract::set_admin(txn, &row.address, row.admin.as_deref())?;
+    contract_history::insert(txn, row)
+}
+
+/// Removes what was derived from `block` onward, undoing changes to older contracts
+pub fn rewind(txn: &mut rusqlite::Transaction, block: u64) -> rusqlite::Result<()> {
+    for change in contract_history::since(txn, block)? {
+        tables::contract::set_code_id(txn, &change.address, change.previous_code_id)?;
+        tables::contract::set_admin(txn, &change.address, change.previous_admin.as_deref())?;
+    }
+    contract_history::delete_since(txn, block)?;
+    tables::contract::delete_since(txn, block)?;
+    tables::contract_execution::delete_since(txn, block)
+}
+
+pub async fn index_wasm(args: &Args, registry: &Registry) {
+    indexer::index_derived(args, CURSOR, |txn, height| {
+        index_wasm_block(txn, registry, height)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::msg::MsgRow;
+
+    fn msg(block: u64) -> MsgRow {
+        MsgRow {
+            block,
+            tx: 0,
+            idx: 0,
+            parent: None,
+            tag: String::new(),
+            data: vec![],
+        }
+    }
+
+    fn index(
+        txn: &mut rusqlite::Transaction,
+        block: u64,
+        kind: &str,
+        code_id: Option<u64>,
+        admin: Option<&str>,
+    ) {
+        let previous = tables::contract::get(txn, "wasm1a").unwrap().unwrap();
+        let code_id = code_id.unwrap_or(previous.code_id);
+        let admin = admin.map(str::to_string);
+        let row = &ContractHistoryRow::new(&msg(block), kind, &previous, code_id, admin);
+        change(txn, row).unwrap();
+    }
+
+    fn contract(txn: &mut rusqlite::Transaction) -> (u64, Option<String>) {
+        let contract = tables::contract::get(txn, "wasm1a").unwrap().unwrap();
+        (contract.code_id, contract.admin)
+    }
+
+    #[test]
+    fn rewind_undoes_migrations_and_admin_changes() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        tables::schema::create(&conn).unwrap();
+        let mut txn = conn.transaction().unwrap();
+
+        let instantiate = model::Instantiate {
+            sender: "cosmos1a".to_string(),
+            admin: Some("cosmos1a".to_string()),
+            code_id: 1,
+            label: "a".to_string(),
+            payload: "{}".to_string(),
+            funds: vec![],
+        };
+        let row = &ContractRow::new(&msg(10), "wasm1a", &instantiate);
+        tables::contract::insert(&mut txn, row).unwrap();
+
+        index(
+            &mut txn,
+            20,
+            contract_history::MIGRATE,
+            Some(2),
+            Some("cosmos1a"),
+        );
+        index(
+            &mut txn,
+            30,
+            contract_history::UPDATE_ADMIN,
+            None,
+            Some("cosmos1b"),
+        );
+        index(&mut txn, 40, contract_history::UPDATE_ADMIN, None, None);
+        assert_eq!(contract(&mut txn), (2, None));
+
+        rewind(&mut txn, 40).unwrap();
+        assert_eq!(contract(&mut txn), (2, Some("cosmos1b".to_string())));
+
+        rewind(&mut txn, 15).unwrap();
+        assert_eq!(contract(&mut txn), (1, Some("cosmos1a".to_string())));
+        assert!(contract_history::since(&mut txn, 0).unwrap().is_empty());
+    }
+}
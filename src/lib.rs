@@ -0,0 +1,12 @@
+pub mod api;
+pub mod args;
+pub mod client;
+pub mod decoder;
+pub mod fetch;
+pub mod fp;
+pub mod genesis;
+pub mod indexer;
+pub mod model;
+pub mod replay;
+pub mod source;
+pub mod tables;
@@ -1,18 +1,24 @@
 use clap::Parser;
-use env_logger;
-use log;
 use std::sync::Arc;
 
-pub mod api;
-pub mod args;
-pub mod fetch;
-pub mod fp;
-pub mod indexer;
-pub mod model;
-pub mod tables;
+use quadrant::{api, args, decoder, genesis, indexer, replay, tables};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Ends a one-shot command, rather than going on to serve or index
+fn exit<E: std::fmt::Display>(command: &str, result: Result<(), E>) -> ! {
+    match result {
+        Ok(()) => {
+            log::info!("Done with {}", command);
+            std::process::exit(0)
+        }
+        Err(err) => {
+            log::error!("Failed to {} : {}", command, err);
+            std::process::exit(1)
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let args = Arc::new(args::Args::parse());
@@ -26,19 +32,59 @@ async fn main() {
     log::info!("Starting Quadrant {}", VERSION);
 
     if args.init_schema {
-        tables::schema::init(&args).unwrap()
+        if let Err(err) = tables::schema::init(&args) {
+            log::error!("Failed to init the schema : {}", err);
+            std::process::exit(1)
+        }
+    }
+
+    if let Some(path) = &args.import_genesis {
+        exit("import the genesis", genesis::import(&args, path))
+    }
+
+    if let Some(dir) = &args.record {
+        exit("record the blocks", replay::record(&args, dir).await)
+    }
+
+    if let Some(dir) = args.replay.clone() {
+        let replay_args = args.clone();
+        tokio::spawn(async move { replay::init(&replay_args, &dir).await });
+    }
+
+    let registry = Arc::new(decoder::Registry::default());
+
+    // Held until exit, a reindex would otherwise rewind derived tables under a running indexer
+    let _lock = if args.index || args.reindex_from.is_some() {
+        match tables::schema::lock(&args.datadir) {
+            Ok(lock) => Some(lock),
+            Err(err) => {
+                log::error!(
+                    "Cannot lock {}, is another --index or --reindex-from running ? {}",
+                    args.datadir.display(),
+                    err
+                );
+                std::process::exit(1)
+            }
+        }
+    } else {
+        None
+    };
+
+    if args.reindex_from.is_some() {
+        exit("reindex", indexer::reindex::reindex(&args, &registry))
     }
 
-    let indexer_args = args.clone();
-    let indexer = if args.index {
-        tokio::spawn(async move { indexer::index_history(&indexer_args).await })
+    let indexers = if args.index {
+        indexer::spawn(args.clone(), registry.clone())
     } else {
-        tokio::spawn(async move {})
+        vec![]
     };
 
     let api_args = args.clone();
-    let api = tokio::spawn(async move { api::init(&api_args).await });
+    let api = tokio::spawn(async move { api::init(&api_args, registry).await });
 
-    indexer.await.unwrap();
+    for indexer in indexers {
+        indexer.await.unwrap();
+    }
     api.await.unwrap();
 }
@@ -7,21 +7,115 @@ pub struct Block {
     pub time: DateTime<Utc>,
     pub proposer: String,
     pub txs: Vec<Tx>,
+    pub begin_block_events: Vec<Event>,
+    pub end_block_events: Vec<Event>,
+    pub last_commit: Option<Commit>,
+    pub archive: Option<Archive>,
+}
+
+/// Raw payloads of a block, zstd compressed, kept to decode it again later
+#[derive(Debug)]
+pub struct Archive {
+    /// Each tx as included in the block
+    pub txs: Vec<Vec<u8>>,
+    /// The `block_results` response as JSON
+    pub results: Vec<u8>,
+}
+
+/// Signatures of the previous block, carried by this one
+#[derive(Debug)]
+pub struct Commit {
+    pub height: u64,
+    pub signatures: Vec<CommitSignature>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitFlag {
+    Absent,
+    Commit,
+    Nil,
+}
+
+impl CommitFlag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommitFlag::Absent => "absent",
+            CommitFlag::Commit => "commit",
+            CommitFlag::Nil => "nil",
+        }
+    }
+}
+
+/// Absent signatures carry no address, it is resolved from the validator set by index
+#[derive(Debug)]
+pub struct CommitSignature {
+    pub index: u32,
+    pub flag: CommitFlag,
+    pub validator_address: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct ValidatorSet {
+    pub height: u64,
+    pub validators: Vec<ValidatorPower>,
+}
+
+#[derive(Debug)]
+pub struct ValidatorPower {
+    pub index: u32,
+    pub address: String,
+    pub voting_power: u64,
+    pub proposer_priority: i64,
 }
 
 #[derive(Debug)]
 pub struct Tx {
     pub hash: String,
     pub index: u32,
+    pub code: u32,
+    pub codespace: String,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+    pub log: String,
+    pub fee: Vec<Coin>,
+    pub gas_limit: u64,
+    pub payer: Option<String>,
+    pub granter: Option<String>,
+    pub memo: String,
+    pub timeout_height: u64,
+    pub signers: Vec<Signer>,
     pub msgs: Vec<Msg>,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug)]
+pub struct Signer {
+    pub index: u32,
+    pub public_key_type: Option<String>,
+    pub public_key: Option<String>,
+    pub sequence: u64,
 }
 
 #[derive(Debug)]
 pub struct Msg {
     pub index: u32,
+    pub parent: Option<u32>,
     pub tag: String,
     pub data: Vec<u8>,
-    pub addresses: Vec<String>,
+    pub addresses: Vec<Address>,
+}
+
+#[derive(Debug)]
+pub struct Address {
+    pub role: String,
+    pub address: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Coin {
+    pub denom: String,
+    pub amount: u128,
 }
 
 #[derive(Debug)]
@@ -29,5 +123,219 @@ pub struct Transfer {
     pub index: u32,
     pub sender: String,
     pub receiver: String,
-    pub amount: u64,
+    pub denom: String,
+    pub amount: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    BeginBlock,
+    Tx,
+    EndBlock,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::BeginBlock => "begin_block",
+            Phase::Tx => "tx",
+            Phase::EndBlock => "end_block",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Event {
+    pub index: u32,
+    pub kind: String,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug)]
+pub struct Attribute {
+    pub index: u32,
+    pub key: String,
+    pub value: String,
+}
+
+/// Domain specific outcome of a message, consumed by the derived indexers
+#[derive(Debug)]
+pub enum Action {
+    SubmitProposal(Proposal),
+    Deposit(Deposit),
+    Vote(Vote),
+    IbcSend(IbcTransfer),
+    IbcReceive(IbcTransfer),
+    IbcAcknowledge(IbcPacket, bool),
+    IbcTimeout(IbcPacket),
+    IbcRefund(IbcTransfer),
+    Grant(Grant),
+    Revoke(Revoke),
+    Instantiate(Instantiate),
+    Execute(Execute),
+    Migrate(Migrate),
+    UpdateAdmin(UpdateAdmin),
+    CreateValidator(Validator),
+    EditValidator(Validator),
+    DelegationChange(DelegationChange),
+    Withdraw(Withdrawal),
+    FundCommunityPool(CommunityPoolFunding),
+}
+
+/// The proposal id is only known once executed, see the `submit_proposal` event
+#[derive(Debug)]
+pub struct Proposal {
+    pub version: String,
+    pub proposer: String,
+    pub content_type: String,
+    pub title: String,
+    pub summary: String,
+    pub initial_deposit: Vec<Coin>,
+}
+
+#[derive(Debug)]
+pub struct Deposit {
+    pub proposal_id: u64,
+    pub depositor: String,
+    pub amount: Vec<Coin>,
+}
+
+#[derive(Debug)]
+pub struct Vote {
+    pub proposal_id: u64,
+    pub voter: String,
+    pub options: Vec<VoteOption>,
+}
+
+#[derive(Debug)]
+pub struct VoteOption {
+    pub option: String,
+    pub weight: String,
+}
+
+/// Identifies a packet on its source chain
+#[derive(Debug)]
+pub struct IbcPacket {
+    pub source_port: String,
+    pub source_channel: String,
+    pub sequence: u64,
+}
+
+/// Outbound transfers only learn their sequence and destination from the `send_packet` event
+#[derive(Debug)]
+pub struct IbcTransfer {
+    pub source_port: String,
+    pub source_channel: String,
+    pub sequence: Option<u64>,
+    pub destination_port: Option<String>,
+    pub destination_channel: Option<String>,
+    pub sender: String,
+    pub receiver: String,
+    pub denom: String,
+    pub amount: u128,
+}
+
+#[derive(Debug)]
+pub struct Grant {
+    pub granter: String,
+    pub grantee: String,
+    pub msg_type_url: String,
+    pub authorization_type: String,
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct Revoke {
+    pub granter: String,
+    pub grantee: String,
+    pub msg_type_url: String,
+}
+
+#[derive(Debug)]
+pub struct Instantiate {
+    pub sender: String,
+    pub admin: Option<String>,
+    pub code_id: u64,
+    pub label: String,
+    pub payload: String,
+    pub funds: Vec<Coin>,
+}
+
+#[derive(Debug)]
+pub struct Execute {
+    pub sender: String,
+    pub contract: String,
+    pub payload: String,
+    pub funds: Vec<Coin>,
+}
+
+#[derive(Debug)]
+pub struct Migrate {
+    pub sender: String,
+    pub contract: String,
+    pub code_id: u64,
+    pub payload: String,
+}
+
+/// Admin is None when cleared
+#[derive(Debug)]
+pub struct UpdateAdmin {
+    pub sender: String,
+    pub contract: String,
+    pub admin: Option<String>,
+}
+
+/// Validator description and commission, fields are None when left unchanged by an edit
+#[derive(Debug)]
+pub struct Validator {
+    pub operator_address: String,
+    pub consensus_pubkey: Option<String>,
+    pub consensus_address: Option<String>,
+    pub moniker: Option<String>,
+    pub identity: Option<String>,
+    pub website: Option<String>,
+    pub security_contact: Option<String>,
+    pub details: Option<String>,
+    pub commission_rate: Option<String>,
+    pub commission_max_rate: Option<String>,
+    pub commission_max_change_rate: Option<String>,
+    pub min_self_delegation: Option<String>,
+}
+
+/// Signed delta of a delegation, a redelegation moves stake out of one validator into another
+#[derive(Debug)]
+pub struct DelegationChange {
+    pub delegator: String,
+    pub validator: String,
+    pub denom: String,
+    pub amount: i128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawalKind {
+    Reward,
+    Commission,
+}
+
+impl WithdrawalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WithdrawalKind::Reward => "reward",
+            WithdrawalKind::Commission => "commission",
+        }
+    }
+}
+
+/// The withdrawn amount is only known from the events of the tx
+#[derive(Debug)]
+pub struct Withdrawal {
+    pub kind: WithdrawalKind,
+    pub address: String,
+    pub validator: String,
+}
+
+#[derive(Debug)]
+pub struct CommunityPoolFunding {
+    pub depositor: String,
+    pub amount: Vec<Coin>,
 }
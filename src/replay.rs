@@ -0,0 +1,138 @@
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Method, Request, Response, Server, StatusCode};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::args::Args;
+use crate::client::Client;
+use crate::source::{BlockSource, DirSource};
+
+/// Saves the `block` and `block_results` responses of `from_block..=to_block` in the layout
+/// read by `DirSource`, along with the `validators` that signed each last commit.
+/// Heights past the latest block are left out.
+pub async fn record(args: &Args, dir: &Path) -> cosmrs::Result<()> {
+    let client = Client::new(args)?;
+    let source = DirSource::new(dir);
+    for endpoint in ["block", "block_results", "validators"] {
+        std::fs::create_dir_all(dir.join(endpoint))?;
+    }
+
+    let lb = args.from_block as u64;
+    let ub = std::cmp::min(client.latest_height().await?, args.to_block as u64);
+    log::info!("Recording range : {} -> {} in {}", lb, ub, dir.display());
+
+    for i in lb..(ub + 1) {
+        let (res, results) = client.raw_block(i).await?;
+        if let Some(commit) = &res.block.last_commit {
+            let height = commit.height.value();
+            let validators = client.validators(height).await?;
+            write(&source.path("validators", height), &validators)?;
+        }
+
+        write(&source.path("block", i), &res)?;
+        write(&source.path("block_results", i), &results)?;
+
+        if i % 100 == 0 {
+            log::info!("Recorded block : {}", i)
+        }
+    }
+    Ok(())
+}
+
+/// Responses are saved with their JSON-RPC envelope, as the RPC sent them
+fn write<R: serde::Serialize>(path: &Path, res: &R) -> cosmrs::Result<()> {
+    let wrapper = json!({ "jsonrpc": "2.0", "id": -1, "result": res });
+    std::fs::write(path, serde_json::to_vec(&wrapper)?)?;
+    Ok(())
+}
+
+fn rpc_error(id: &Value, code: i32, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message, "data": "" },
+    })
+}
+
+/// The recorded response of a JSON-RPC request, or a JSON-RPC error.
+/// A request without height gets the latest recorded one, as a node would.
+async fn replay(source: &DirSource, request: &Value) -> Vec<u8> {
+    let id = &request["id"];
+    let method = request["method"].as_str().unwrap_or_default();
+    if !["block", "block_results", "validators"].contains(&method) {
+        return rpc_error(id, -32601, "Method not found")
+            .to_string()
+            .into_bytes();
+    }
+
+    let height = match request["params"]["height"].as_str() {
+        Some(height) => height.parse().ok(),
+        None => source.latest_height().await.ok(),
+    };
+    let recorded = match height {
+        Some(height) => tokio::fs::read(source.path(method, height)).await.ok(),
+        None => None,
+    };
+    recorded.unwrap_or_else(|| {
+        rpc_error(id, -32603, "Height not recorded")
+            .to_string()
+            .into_bytes()
+    })
+}
+
+async fn handle(
+    source: Arc<DirSource>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    if request.method() != Method::POST {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        return Ok(response);
+    }
+
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .unwrap_or_default();
+    let body = match serde_json::from_slice::<Value>(&body) {
+        Ok(request) => replay(&source, &request).await,
+        Err(_) => rpc_error(&Value::Null, -32700, "Parse error")
+            .to_string()
+            .into_bytes(),
+    };
+
+    let mut response = Response::new(Body::from(body));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    Ok(response)
+}
+
+/// Serves recorded responses as a stand-in for the Tendermint RPC of a node.
+/// Only `block`, `block_results` and `validators` are answered, over HTTP POST,
+/// the WebSocket is not served so that indexers fall back to polling.
+/// Returns the bound address along with the server to await.
+pub fn serve(
+    dir: &Path,
+    addr: &SocketAddr,
+) -> hyper::Result<(SocketAddr, impl Future<Output = hyper::Result<()>>)> {
+    let source = Arc::new(DirSource::new(dir));
+    let service = make_service_fn(move |_| {
+        let source = source.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(source.clone(), request))) }
+    });
+
+    let server = Server::try_bind(addr)?.serve(service);
+    Ok((server.local_addr(), server))
+}
+
+/// Runs the replay server of `--replay` until it fails
+pub async fn init(args: &Args, dir: &Path) {
+    let (addr, server) = serve(dir, &args.replay_listen).unwrap();
+    log::info!("Replaying {} on {}", dir.display(), addr);
+    server.await.unwrap()
+}
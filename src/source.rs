@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use cosmrs::rpc::endpoint::{block, block_results, validators};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::time::Duration;
+
+use crate::args::Args;
+use crate::client::{Client, Error, NewBlocks, Result};
+use crate::decoder::Registry;
+use crate::fetch;
+use crate::model;
+
+/// Where the history indexer gets its blocks from
+#[async_trait]
+pub trait BlockSource: Send + Sync {
+    /// Height of the most recent block available
+    async fn latest_height(&self) -> Result<u64>;
+
+    /// The `block` and `block_results` responses of a height, left undecoded
+    async fn raw_block(&self, height: u64) -> Result<(block::Response, block_results::Response)>;
+
+    /// The validator set of a height, in the order of its commit signatures
+    async fn validator_set(&self, height: u64) -> Result<model::ValidatorSet>;
+
+    /// Subscription to new blocks, `None` when the source cannot announce them
+    async fn new_blocks(&self) -> Option<Result<NewBlocks>> {
+        None
+    }
+
+    /// Total time requests spent waiting on a rate limit
+    fn throttled(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Fetches a block, decoding and archiving happen on the blocking pool
+    async fn block(
+        &self,
+        registry: Arc<Registry>,
+        height: u64,
+        archive: bool,
+    ) -> Result<model::Block> {
+        let (res, results) = self.raw_block(height).await?;
+        tokio::task::spawn_blocking(move || {
+            let mut block = fetch::block_to_model(&registry, &res, &results)?;
+            if archive {
+                block.archive = Some(fetch::block_to_archive(&res, &results)?);
+            }
+            Ok(block)
+        })
+        .await
+        .map_err(|err| Error::Decode(cosmrs::ErrorReport::msg(err.to_string())))?
+        .map_err(Error::Decode)
+    }
+}
+
+/// The recorded directory when one is given, the RPC endpoints otherwise
+pub fn open(args: &Args) -> Result<Box<dyn BlockSource>> {
+    Ok(match &args.source_dir {
+        Some(dir) => Box::new(DirSource::new(dir)),
+        None => Box::new(Client::new(args)?),
+    })
+}
+
+/// Recorded JSON-RPC responses, laid out as
+/// `block/<height>.json`, `block_results/<height>.json` and `validators/<height>.json`.
+/// The validators of a height are a single response holding every page.
+pub struct DirSource {
+    dir: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(dir: &Path) -> Self {
+        DirSource {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    pub fn path(&self, endpoint: &str, height: u64) -> PathBuf {
+        self.dir.join(endpoint).join(format!("{}.json", height))
+    }
+
+    async fn read<R: cosmrs::rpc::Response>(&self, endpoint: &str, height: u64) -> Result<R> {
+        let data = tokio::fs::read(self.path(endpoint, height))
+            .await
+            .map_err(Error::Io)?;
+        R::from_string(data).map_err(|err| Error::Rpc(Box::new(err)))
+    }
+}
+
+#[async_trait]
+impl BlockSource for DirSource {
+    async fn latest_height(&self) -> Result<u64> {
+        let mut entries = tokio::fs::read_dir(self.dir.join("block"))
+            .await
+            .map_err(Error::Io)?;
+
+        let mut latest = None;
+        while let Some(entry) = entries.next_entry().await.map_err(Error::Io)? {
+            let path = entry.path();
+            let height = path
+                .file_stem()
+                .and_then(|it| it.to_str())
+                .and_then(|it| it.parse::<u64>().ok());
+            latest = std::cmp::max(latest, height);
+        }
+
+        latest.ok_or_else(|| {
+            Error::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No recorded block in {}", self.dir.display()),
+            ))
+        })
+    }
+
+    async fn raw_block(&self, height: u64) -> Result<(block::Response, block_results::Response)> {
+        let res = self.read("block", height).await?;
+        let results = self.read("block_results", height).await?;
+        Ok((res, results))
+    }
+
+    async fn validator_set(&self, height: u64) -> Result<model::ValidatorSet> {
+        let res: validators::Response = self.read("validators", height).await?;
+        Ok(fetch::validator_set_to_model(&res))
+    }
+}
@@ -5,6 +5,7 @@ use rusqlite::*;
 #[derive(Debug)]
 pub struct AddressMsgRow {
     pub address: String,
+    pub role: String,
     pub block: u64,
     pub tx: u32,
     pub msg: u32,
@@ -16,17 +17,24 @@ impl TryFrom<&Row<'_>> for AddressMsgRow {
     fn try_from(row: &Row) -> Result<Self, Self::Error> {
         Ok(AddressMsgRow {
             address: row.get(0)?,
-            block: row.get(1)?,
-            tx: row.get(2)?,
-            msg: row.get(3)?,
+            role: row.get(1)?,
+            block: row.get(2)?,
+            tx: row.get(3)?,
+            msg: row.get(4)?,
         })
     }
 }
 
 impl AddressMsgRow {
-    pub fn new(block: &model::Block, tx: &model::Tx, msg: &model::Msg, address: &String) -> Self {
+    pub fn new(
+        block: &model::Block,
+        tx: &model::Tx,
+        msg: &model::Msg,
+        address: &model::Address,
+    ) -> Self {
         AddressMsgRow {
-            address: address.clone(),
+            address: address.address.clone(),
+            role: address.role.clone(),
             block: block.height,
             tx: tx.index,
             msg: msg.index,
@@ -34,12 +42,22 @@ impl AddressMsgRow {
     }
 }
 
-const INSERT: &str = "INSERT INTO address_msg (address, block, tx, msg) VALUES (?,?,?,?)";
+const INSERT: &str = "INSERT INTO address_msg (address, role, block, tx, msg) VALUES (?,?,?,?,?)";
 pub fn insert<T>(conn: &mut T, row: &AddressMsgRow) -> Result<()>
 where
     T: core::ops::Deref<Target = Connection>,
 {
     conn.prepare_cached(INSERT)?
-        .execute(params![row.address, row.block, row.tx, row.msg])
+        .execute(params![row.address, row.role, row.block, row.tx, row.msg])
+        .map(fp::as_unit)
+}
+
+const DELETE_BY_BLOCK: &str = "DELETE FROM address_msg WHERE block = ?";
+pub fn delete_by_block<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_BY_BLOCK)?
+        .execute(params![block])
         .map(fp::as_unit)
 }
@@ -0,0 +1,81 @@
+use crate::fp;
+use rusqlite::*;
+
+/// A raw tx as included in its block, zstd compressed
+#[derive(Debug)]
+pub struct ArchiveTxRow {
+    pub block: u64,
+    pub idx: u32,
+    pub data: Vec<u8>,
+}
+
+impl TryFrom<&Row<'_>> for ArchiveTxRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ArchiveTxRow {
+            block: row.get(0)?,
+            idx: row.get(1)?,
+            data: row.get(2)?,
+        })
+    }
+}
+
+/// The `block_results` response of a block as JSON, zstd compressed
+#[derive(Debug)]
+pub struct ArchiveBlockResultsRow {
+    pub block: u64,
+    pub data: Vec<u8>,
+}
+
+impl TryFrom<&Row<'_>> for ArchiveBlockResultsRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ArchiveBlockResultsRow {
+            block: row.get(0)?,
+            data: row.get(1)?,
+        })
+    }
+}
+
+const INSERT_TX: &str = "INSERT INTO archive_tx (block, idx, data) VALUES (?,?,?)";
+pub fn insert_tx<T>(conn: &mut T, row: &ArchiveTxRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_TX)?
+        .execute(params![row.block, row.idx, row.data])
+        .map(fp::as_unit)
+}
+
+const INSERT_BLOCK_RESULTS: &str = "INSERT INTO archive_block_results (block, data) VALUES (?,?)";
+pub fn insert_block_results<T>(conn: &mut T, row: &ArchiveBlockResultsRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_BLOCK_RESULTS)?
+        .execute(params![row.block, row.data])
+        .map(fp::as_unit)
+}
+
+const TXS_BY_BLOCK: &str = "SELECT block, idx, data FROM archive_tx WHERE block = ? ORDER BY idx";
+pub fn txs_by_block<T>(conn: &mut T, block: u64) -> Result<Vec<ArchiveTxRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(TXS_BY_BLOCK)?
+        .query_map(params![block], |row| ArchiveTxRow::try_from(row))?
+        .collect()
+}
+
+const BLOCK_RESULTS_BY_BLOCK: &str =
+    "SELECT block, data FROM archive_block_results WHERE block = ?";
+pub fn block_results_by_block<T>(conn: &mut T, block: u64) -> Result<Option<ArchiveBlockResultsRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BLOCK_RESULTS_BY_BLOCK)?
+        .query_row(params![block], |row| ArchiveBlockResultsRow::try_from(row))
+        .optional()
+}
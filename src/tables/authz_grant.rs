@@ -0,0 +1,102 @@
+use crate::fp;
+use crate::model;
+use crate::tables::msg::MsgRow;
+use chrono::{DateTime, Utc};
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct AuthzGrantRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub granter: String,
+    pub grantee: String,
+    pub msg_type_url: String,
+    pub authorization_type: String,
+    pub expiration: Option<DateTime<Utc>>,
+    /// Height at which the grant was revoked or replaced
+    pub revoked: Option<u64>,
+}
+
+impl TryFrom<&Row<'_>> for AuthzGrantRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(AuthzGrantRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            msg: row.get(2)?,
+            granter: row.get(3)?,
+            grantee: row.get(4)?,
+            msg_type_url: row.get(5)?,
+            authorization_type: row.get(6)?,
+            expiration: row.get(7)?,
+            revoked: row.get(8)?,
+        })
+    }
+}
+
+impl AuthzGrantRow {
+    pub fn new(msg: &MsgRow, grant: &model::Grant) -> Self {
+        AuthzGrantRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            granter: grant.granter.clone(),
+            grantee: grant.grantee.clone(),
+            msg_type_url: grant.msg_type_url.clone(),
+            authorization_type: grant.authorization_type.clone(),
+            expiration: grant.expiration,
+            revoked: None,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO authz_grant (block, tx, msg, granter, grantee, msg_type_url, authorization_type, expiration, revoked) VALUES (?,?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &AuthzGrantRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.granter,
+            row.grantee,
+            row.msg_type_url,
+            row.authorization_type,
+            row.expiration,
+            row.revoked
+        ])
+        .map(fp::as_unit)
+}
+
+const REVOKE: &str = "UPDATE authz_grant SET revoked = ? WHERE granter = ? AND grantee = ? AND msg_type_url = ? AND revoked IS NULL";
+pub fn revoke<T>(
+    conn: &mut T,
+    granter: &str,
+    grantee: &str,
+    msg_type_url: &str,
+    block: u64,
+) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(REVOKE)?
+        .execute(params![block, granter, grantee, msg_type_url])
+        .map(fp::as_unit)
+}
+
+const DELETE_SINCE: &str = "DELETE FROM authz_grant WHERE block >= ?";
+const UNREVOKE_SINCE: &str = "UPDATE authz_grant SET revoked = NULL WHERE revoked >= ?";
+/// Removes the grants of `block` onward, along with the revocations of earlier grants
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?.execute(params![block])?;
+    conn.prepare_cached(UNREVOKE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
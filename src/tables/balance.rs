@@ -0,0 +1,146 @@
+use crate::fp;
+use crate::tables;
+use rusqlite::*;
+
+/// Running balance of an address, `block` is the last height it changed at.
+/// Amounts are signed as balances held before the first indexed block are unknown
+/// unless seeded from genesis.
+#[derive(Debug)]
+pub struct BalanceRow {
+    pub block: u64,
+    pub address: String,
+    pub denom: String,
+    pub amount: i128,
+}
+
+impl TryFrom<&Row<'_>> for BalanceRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(BalanceRow {
+            block: row.get(0)?,
+            address: row.get(1)?,
+            denom: row.get(2)?,
+            amount: tables::get_signed_amount(row, 3)?,
+        })
+    }
+}
+
+impl BalanceRow {
+    pub fn new(block: u64, address: &str, denom: &str, amount: i128) -> Self {
+        BalanceRow {
+            block,
+            address: address.to_string(),
+            denom: denom.to_string(),
+            amount,
+        }
+    }
+}
+
+const GET: &str =
+    "SELECT block, address, denom, amount FROM balance WHERE address = ? AND denom = ?";
+pub fn get<T>(conn: &mut T, address: &str, denom: &str) -> Result<Option<BalanceRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(GET)?
+        .query_row(params![address, denom], |row| BalanceRow::try_from(row))
+        .optional()
+}
+
+const SET: &str = "INSERT OR REPLACE INTO balance (block, address, denom, amount) VALUES (?,?,?,?)";
+pub fn set<T>(conn: &mut T, row: &BalanceRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(SET)?
+        .execute(params![
+            row.block,
+            row.address,
+            row.denom,
+            row.amount.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+const INSERT_CHECKPOINT: &str =
+    "INSERT INTO balance_checkpoint (block, address, denom, amount) VALUES (?,?,?,?)";
+pub fn insert_checkpoint<T>(conn: &mut T, row: &BalanceRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_CHECKPOINT)?
+        .execute(params![
+            row.block,
+            row.address,
+            row.denom,
+            row.amount.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+// Only the balances that changed since the previous checkpoint are materialized
+const CHECKPOINT: &str = "
+    INSERT INTO balance_checkpoint (block, address, denom, amount)
+    SELECT ?1, address, denom, amount FROM balance
+    WHERE block > COALESCE((SELECT MAX(block) FROM balance_checkpoint WHERE block < ?1), -1)";
+pub fn checkpoint<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(CHECKPOINT)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
+
+const LAST_CHECKPOINT: &str = "SELECT MAX(block) FROM balance_checkpoint WHERE block <= ?";
+/// Height of the latest checkpoint at or below `block`
+pub fn last_checkpoint<T>(conn: &mut T, block: u64) -> Result<Option<u64>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(LAST_CHECKPOINT)?
+        .query_row(params![block], |row| row.get(0))
+}
+
+const CHECKPOINTS_BY_ADDRESS: &str = "
+    SELECT c.block, c.address, c.denom, c.amount FROM balance_checkpoint c
+    WHERE c.address = ?1 AND c.block = (
+        SELECT MAX(block) FROM balance_checkpoint
+        WHERE address = c.address AND denom = c.denom AND block <= ?2
+    )";
+/// Latest checkpointed balance of each denom held by the address at or below `block`
+pub fn checkpoints_by_address<T>(conn: &mut T, address: &str, block: u64) -> Result<Vec<BalanceRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(CHECKPOINTS_BY_ADDRESS)?
+        .query_map(params![address, block], |row| BalanceRow::try_from(row))?
+        .collect()
+}
+
+const CHECKPOINTS_BY_DENOM: &str = "
+    SELECT c.block, c.address, c.denom, c.amount FROM balance_checkpoint c
+    WHERE c.denom = ?1 AND c.block = (
+        SELECT MAX(block) FROM balance_checkpoint
+        WHERE address = c.address AND denom = c.denom AND block <= ?2
+    )";
+/// Latest checkpointed balance of every holder of the denom at or below `block`
+pub fn checkpoints_by_denom<T>(conn: &mut T, denom: &str, block: u64) -> Result<Vec<BalanceRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(CHECKPOINTS_BY_DENOM)?
+        .query_map(params![denom, block], |row| BalanceRow::try_from(row))?
+        .collect()
+}
+
+const DELETE_CHECKPOINTS_SINCE: &str = "DELETE FROM balance_checkpoint WHERE block >= ?";
+pub fn delete_checkpoints_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_CHECKPOINTS_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
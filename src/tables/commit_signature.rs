@@ -0,0 +1,113 @@
+use crate::fp;
+use crate::model;
+use chrono::{DateTime, Utc};
+use rusqlite::*;
+
+/// `block` is the height that was signed, one less than the block carrying the commit
+#[derive(Debug)]
+pub struct CommitSignatureRow {
+    pub block: u64,
+    pub idx: u32,
+    pub validator_address: Option<String>,
+    pub flag: String,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<&Row<'_>> for CommitSignatureRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(CommitSignatureRow {
+            block: row.get(0)?,
+            idx: row.get(1)?,
+            validator_address: row.get(2)?,
+            flag: row.get(3)?,
+            timestamp: row.get(4)?,
+        })
+    }
+}
+
+impl CommitSignatureRow {
+    pub fn new(commit: &model::Commit, sig: &model::CommitSignature) -> Self {
+        CommitSignatureRow {
+            block: commit.height,
+            idx: sig.index,
+            validator_address: sig.validator_address.clone(),
+            flag: sig.flag.as_str().to_string(),
+            timestamp: sig.timestamp,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO commit_signature (block, idx, validator_address, flag, timestamp) VALUES (?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &CommitSignatureRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.idx,
+            row.validator_address,
+            row.flag,
+            row.timestamp
+        ])
+        .map(fp::as_unit)
+}
+
+#[derive(Debug)]
+pub struct UptimeRow {
+    pub blocks: u64,
+    pub signed: u64,
+    pub nil: u64,
+    pub absent: u64,
+}
+
+const UPTIME: &str = "SELECT COUNT(*), COALESCE(SUM(flag = 'commit'), 0), COALESCE(SUM(flag = 'nil'), 0), COALESCE(SUM(flag = 'absent'), 0) FROM commit_signature WHERE validator_address = ? AND block BETWEEN ? AND ?";
+pub fn uptime<T>(conn: &mut T, address: &str, from: u64, to: u64) -> Result<UptimeRow>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(UPTIME)?
+        .query_row(params![address, from, to], |row| {
+            Ok(UptimeRow {
+                blocks: row.get(0)?,
+                signed: row.get(1)?,
+                nil: row.get(2)?,
+                absent: row.get(3)?,
+            })
+        })
+}
+
+#[derive(Debug)]
+pub struct StreakRow {
+    pub start: u64,
+    pub end: u64,
+    pub length: u64,
+}
+
+// Consecutive absent heights share the same `block - row_number` value
+const MISSED_STREAKS: &str = "SELECT MIN(block), MAX(block), COUNT(*) FROM (
+        SELECT block, block - ROW_NUMBER() OVER (ORDER BY block) AS streak FROM commit_signature
+        WHERE validator_address = ? AND block BETWEEN ? AND ? AND flag = 'absent'
+    ) GROUP BY streak HAVING COUNT(*) >= ? ORDER BY MIN(block)";
+pub fn missed_streaks<T>(
+    conn: &mut T,
+    address: &str,
+    from: u64,
+    to: u64,
+    min_length: u64,
+) -> Result<Vec<StreakRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(MISSED_STREAKS)?
+        .query_map(params![address, from, to, min_length], |row| {
+            Ok(StreakRow {
+                start: row.get(0)?,
+                end: row.get(1)?,
+                length: row.get(2)?,
+            })
+        })?
+        .collect()
+}
@@ -0,0 +1,107 @@
+use crate::fp;
+use crate::model;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct ContractRow {
+    pub address: String,
+    pub code_id: u64,
+    pub creator: String,
+    pub admin: Option<String>,
+    pub label: String,
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+}
+
+impl TryFrom<&Row<'_>> for ContractRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ContractRow {
+            address: row.get(0)?,
+            code_id: row.get(1)?,
+            creator: row.get(2)?,
+            admin: row.get(3)?,
+            label: row.get(4)?,
+            block: row.get(5)?,
+            tx: row.get(6)?,
+            msg: row.get(7)?,
+        })
+    }
+}
+
+impl ContractRow {
+    pub fn new(msg: &MsgRow, address: &str, instantiate: &model::Instantiate) -> Self {
+        ContractRow {
+            address: address.to_string(),
+            code_id: instantiate.code_id,
+            creator: instantiate.sender.clone(),
+            admin: instantiate.admin.clone(),
+            label: instantiate.label.clone(),
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO contract (address, code_id, creator, admin, label, block, tx, msg) VALUES (?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &ContractRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.address,
+            row.code_id,
+            row.creator,
+            row.admin,
+            row.label,
+            row.block,
+            row.tx,
+            row.msg
+        ])
+        .map(fp::as_unit)
+}
+
+const GET: &str = "SELECT address, code_id, creator, admin, label, block, tx, msg FROM contract WHERE address = ?";
+pub fn get<T>(conn: &mut T, address: &str) -> Result<Option<ContractRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(GET)?
+        .query_row(params![address], |row| ContractRow::try_from(row))
+        .optional()
+}
+
+const SET_CODE_ID: &str = "UPDATE contract SET code_id = ? WHERE address = ?";
+pub fn set_code_id<T>(conn: &mut T, address: &str, code_id: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(SET_CODE_ID)?
+        .execute(params![code_id, address])
+        .map(fp::as_unit)
+}
+
+const SET_ADMIN: &str = "UPDATE contract SET admin = ? WHERE address = ?";
+pub fn set_admin<T>(conn: &mut T, address: &str, admin: Option<&str>) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(SET_ADMIN)?
+        .execute(params![admin, address])
+        .map(fp::as_unit)
+}
+
+const DELETE_SINCE: &str = "DELETE FROM contract WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
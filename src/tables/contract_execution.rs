@@ -0,0 +1,87 @@
+use crate::fp;
+use crate::model;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct ContractExecutionRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub contract: String,
+    pub sender: String,
+    /// Execute message as sent, usually JSON
+    pub payload: String,
+}
+
+impl TryFrom<&Row<'_>> for ContractExecutionRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ContractExecutionRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            msg: row.get(2)?,
+            contract: row.get(3)?,
+            sender: row.get(4)?,
+            payload: row.get(5)?,
+        })
+    }
+}
+
+impl ContractExecutionRow {
+    pub fn new(msg: &MsgRow, execute: &model::Execute) -> Self {
+        ContractExecutionRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            contract: execute.contract.clone(),
+            sender: execute.sender.clone(),
+            payload: execute.payload.clone(),
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO contract_execution (block, tx, msg, contract, sender, payload) VALUES (?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &ContractExecutionRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.contract,
+            row.sender,
+            row.payload
+        ])
+        .map(fp::as_unit)
+}
+
+const PAGE_BY_CONTRACT: &str = "SELECT block, tx, msg, contract, sender, payload FROM contract_execution WHERE contract = ? ORDER BY block DESC, tx DESC, msg DESC LIMIT ? OFFSET ?";
+pub fn page_by_contract<T>(
+    conn: &mut T,
+    contract: &str,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<ContractExecutionRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(PAGE_BY_CONTRACT)?
+        .query_map(params![contract, limit, offset], |row| {
+            ContractExecutionRow::try_from(row)
+        })?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM contract_execution WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,102 @@
+use crate::fp;
+use crate::tables::contract::ContractRow;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+pub const MIGRATE: &str = "migrate";
+pub const UPDATE_ADMIN: &str = "update_admin";
+
+/// One row per migrate or admin change, with the values it replaced so it can be undone
+#[derive(Debug)]
+pub struct ContractHistoryRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub address: String,
+    pub kind: String,
+    pub code_id: u64,
+    pub admin: Option<String>,
+    pub previous_code_id: u64,
+    pub previous_admin: Option<String>,
+}
+
+impl TryFrom<&Row<'_>> for ContractHistoryRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ContractHistoryRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            msg: row.get(2)?,
+            address: row.get(3)?,
+            kind: row.get(4)?,
+            code_id: row.get(5)?,
+            admin: row.get(6)?,
+            previous_code_id: row.get(7)?,
+            previous_admin: row.get(8)?,
+        })
+    }
+}
+
+impl ContractHistoryRow {
+    pub fn new(
+        msg: &MsgRow,
+        kind: &str,
+        previous: &ContractRow,
+        code_id: u64,
+        admin: Option<String>,
+    ) -> Self {
+        ContractHistoryRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            address: previous.address.clone(),
+            kind: kind.to_string(),
+            code_id,
+            admin,
+            previous_code_id: previous.code_id,
+            previous_admin: previous.admin.clone(),
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO contract_history (block, tx, msg, address, kind, code_id, admin, previous_code_id, previous_admin) VALUES (?,?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &ContractHistoryRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.address,
+            row.kind,
+            row.code_id,
+            row.admin,
+            row.previous_code_id,
+            row.previous_admin
+        ])
+        .map(fp::as_unit)
+}
+
+/// Latest first, the order in which changes are undone
+const SINCE: &str = "SELECT block, tx, msg, address, kind, code_id, admin, previous_code_id, previous_admin FROM contract_history WHERE block >= ? ORDER BY block DESC, tx DESC, msg DESC";
+pub fn since<T>(conn: &mut T, block: u64) -> Result<Vec<ContractHistoryRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(SINCE)?
+        .query_map(params![block], |row| ContractHistoryRow::try_from(row))?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM contract_history WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
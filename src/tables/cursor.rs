@@ -0,0 +1,22 @@
+use crate::fp;
+use rusqlite::*;
+
+const GET: &str = "SELECT height FROM cursor WHERE name = ?";
+pub fn get<T>(conn: &mut T, name: &str) -> Result<Option<u64>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(GET)?
+        .query_row(params![name], |row| row.get(0))
+        .optional()
+}
+
+const SET: &str = "INSERT OR REPLACE INTO cursor (name, height) VALUES (?,?)";
+pub fn set<T>(conn: &mut T, name: &str, height: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(SET)?
+        .execute(params![name, height])
+        .map(fp::as_unit)
+}
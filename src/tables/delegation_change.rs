@@ -0,0 +1,106 @@
+use crate::fp;
+use crate::model;
+use crate::tables;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct DelegationChangeRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub idx: u32,
+    pub delegator: String,
+    pub validator: String,
+    pub denom: String,
+    pub amount: i128,
+}
+
+impl TryFrom<&Row<'_>> for DelegationChangeRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(DelegationChangeRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            msg: row.get(2)?,
+            idx: row.get(3)?,
+            delegator: row.get(4)?,
+            validator: row.get(5)?,
+            denom: row.get(6)?,
+            amount: tables::get_signed_amount(row, 7)?,
+        })
+    }
+}
+
+impl DelegationChangeRow {
+    pub fn new(msg: &MsgRow, idx: u32, change: &model::DelegationChange) -> Self {
+        DelegationChangeRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            idx,
+            delegator: change.delegator.clone(),
+            validator: change.validator.clone(),
+            denom: change.denom.clone(),
+            amount: change.amount,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO delegation_change (block, tx, msg, idx, delegator, validator, denom, amount) VALUES (?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &DelegationChangeRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.idx,
+            row.delegator,
+            row.validator,
+            row.denom,
+            row.amount.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+// Amounts may not fit in 64 bits, they are summed by the caller rather than in SQL
+const BY_DELEGATOR: &str = "SELECT block, tx, msg, idx, delegator, validator, denom, amount FROM delegation_change WHERE delegator = ? ORDER BY block, tx, msg, idx";
+pub fn by_delegator<T>(conn: &mut T, delegator: &str) -> Result<Vec<DelegationChangeRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_DELEGATOR)?
+        .query_map(params![delegator], |row| DelegationChangeRow::try_from(row))?
+        .collect()
+}
+
+const BY_VALIDATOR: &str = "SELECT block, tx, msg, idx, delegator, validator, denom, amount FROM delegation_change WHERE validator = ? AND block BETWEEN ? AND ? ORDER BY block, tx, msg, idx";
+pub fn by_validator<T>(
+    conn: &mut T,
+    validator: &str,
+    from: u64,
+    to: u64,
+) -> Result<Vec<DelegationChangeRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_VALIDATOR)?
+        .query_map(params![validator, from, to], |row| {
+            DelegationChangeRow::try_from(row)
+        })?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM delegation_change WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,184 @@
+use crate::fp;
+use crate::model;
+use rusqlite::*;
+
+/// Events emitted outside of a tx (begin/end block) have a NULL `tx`
+#[derive(Debug)]
+pub struct EventRow {
+    pub block: u64,
+    pub phase: String,
+    pub tx: Option<u32>,
+    pub idx: u32,
+    pub kind: String,
+}
+
+impl TryFrom<&Row<'_>> for EventRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(EventRow {
+            block: row.get(0)?,
+            phase: row.get(1)?,
+            tx: row.get(2)?,
+            idx: row.get(3)?,
+            kind: row.get(4)?,
+        })
+    }
+}
+
+impl EventRow {
+    pub fn new(
+        block: &model::Block,
+        phase: model::Phase,
+        tx: Option<&model::Tx>,
+        event: &model::Event,
+    ) -> Self {
+        EventRow {
+            block: block.height,
+            phase: phase.as_str().to_string(),
+            tx: tx.map(|tx| tx.index),
+            idx: event.index,
+            kind: event.kind.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EventAttributeRow {
+    pub block: u64,
+    pub phase: String,
+    pub tx: Option<u32>,
+    pub event: u32,
+    pub idx: u32,
+    pub key: String,
+    pub value: String,
+}
+
+impl TryFrom<&Row<'_>> for EventAttributeRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(EventAttributeRow {
+            block: row.get(0)?,
+            phase: row.get(1)?,
+            tx: row.get(2)?,
+            event: row.get(3)?,
+            idx: row.get(4)?,
+            key: row.get(5)?,
+            value: row.get(6)?,
+        })
+    }
+}
+
+impl EventAttributeRow {
+    pub fn new(event: &EventRow, attribute: &model::Attribute) -> Self {
+        EventAttributeRow {
+            block: event.block,
+            phase: event.phase.clone(),
+            tx: event.tx,
+            event: event.idx,
+            idx: attribute.index,
+            key: attribute.key.clone(),
+            value: attribute.value.clone(),
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO event (block, phase, tx, idx, type) VALUES (?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &EventRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![row.block, row.phase, row.tx, row.idx, row.kind])
+        .map(fp::as_unit)
+}
+
+const INSERT_ATTRIBUTE: &str =
+    "INSERT INTO event_attribute (block, phase, tx, event, idx, key, value) VALUES (?,?,?,?,?,?,?)";
+pub fn insert_attribute<T>(conn: &mut T, row: &EventAttributeRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_ATTRIBUTE)?
+        .execute(params![
+            row.block, row.phase, row.tx, row.event, row.idx, row.key, row.value
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_BLOCK: &str =
+    "SELECT block, phase, tx, idx, type FROM event WHERE block = ? ORDER BY rowid";
+pub fn by_block<T>(conn: &mut T, block: u64) -> Result<Vec<EventRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_BLOCK)?
+        .query_map(params![block], |row| EventRow::try_from(row))?
+        .collect()
+}
+
+const BY_TX: &str =
+    "SELECT block, phase, tx, idx, type FROM event WHERE block = ? AND tx = ? ORDER BY idx";
+pub fn by_tx<T>(conn: &mut T, block: u64, tx: u32) -> Result<Vec<EventRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_TX)?
+        .query_map(params![block, tx], |row| EventRow::try_from(row))?
+        .collect()
+}
+
+const ATTRIBUTES_BY_BLOCK: &str = "SELECT block, phase, tx, event, idx, key, value FROM event_attribute WHERE block = ? ORDER BY rowid";
+pub fn attributes_by_block<T>(conn: &mut T, block: u64) -> Result<Vec<EventAttributeRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(ATTRIBUTES_BY_BLOCK)?
+        .query_map(params![block], |row| EventAttributeRow::try_from(row))?
+        .collect()
+}
+
+const ATTRIBUTES_BY_TX: &str = "SELECT block, phase, tx, event, idx, key, value FROM event_attribute WHERE block = ? AND tx = ? ORDER BY event, idx";
+pub fn attributes_by_tx<T>(conn: &mut T, block: u64, tx: u32) -> Result<Vec<EventAttributeRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(ATTRIBUTES_BY_TX)?
+        .query_map(params![block, tx], |row| EventAttributeRow::try_from(row))?
+        .collect()
+}
+
+const ATTRIBUTE_VALUES: &str = "
+    SELECT a.value FROM event_attribute a
+    JOIN event e ON e.block = a.block AND e.phase = a.phase AND e.tx = a.tx AND e.idx = a.event
+    WHERE a.block = ? AND a.tx = ? AND e.type = ? AND a.key = ?
+    ORDER BY a.event, a.idx";
+/// Values of one attribute across the events of a given type emitted by a tx, in emission order
+pub fn attribute_values<T>(
+    conn: &mut T,
+    block: u64,
+    tx: u32,
+    kind: &str,
+    key: &str,
+) -> Result<Vec<String>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(ATTRIBUTE_VALUES)?
+        .query_map(params![block, tx, kind, key], |row| row.get(0))?
+        .collect()
+}
+
+const DELETE_BY_BLOCK: &str = "DELETE FROM event WHERE block = ?";
+const DELETE_ATTRIBUTES_BY_BLOCK: &str = "DELETE FROM event_attribute WHERE block = ?";
+pub fn delete_by_block<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_ATTRIBUTES_BY_BLOCK)?
+        .execute(params![block])?;
+    conn.prepare_cached(DELETE_BY_BLOCK)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,253 @@
+use crate::fp;
+use crate::tables;
+use rusqlite::*;
+
+/// The imported genesis, every other genesis table describes the state at its height
+#[derive(Debug)]
+pub struct GenesisRow {
+    pub chain_id: String,
+    pub height: u64,
+    pub time: String,
+}
+
+impl TryFrom<&Row<'_>> for GenesisRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(GenesisRow {
+            chain_id: row.get(0)?,
+            height: row.get(1)?,
+            time: row.get(2)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct GenesisAccountRow {
+    pub address: String,
+    pub kind: String,
+    pub account_number: Option<String>,
+    pub sequence: Option<String>,
+    /// Module name of module accounts
+    pub name: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct GenesisBalanceRow {
+    pub address: String,
+    pub denom: String,
+    pub amount: u128,
+}
+
+impl TryFrom<&Row<'_>> for GenesisBalanceRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(GenesisBalanceRow {
+            address: row.get(0)?,
+            denom: row.get(1)?,
+            amount: tables::get_amount(row, 2)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct GenesisValidatorRow {
+    pub operator_address: String,
+    pub consensus_pubkey: Option<String>,
+    pub consensus_address: Option<String>,
+    pub moniker: String,
+    pub status: String,
+    pub jailed: bool,
+    pub tokens: u128,
+    pub delegator_shares: String,
+    pub commission_rate: String,
+}
+
+impl TryFrom<&Row<'_>> for GenesisValidatorRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(GenesisValidatorRow {
+            operator_address: row.get(0)?,
+            consensus_pubkey: row.get(1)?,
+            consensus_address: row.get(2)?,
+            moniker: row.get(3)?,
+            status: row.get(4)?,
+            jailed: row.get(5)?,
+            tokens: tables::get_amount(row, 6)?,
+            delegator_shares: row.get(7)?,
+            commission_rate: row.get(8)?,
+        })
+    }
+}
+
+/// Delegations are held as shares, `amount` is their value in tokens at genesis
+#[derive(Debug)]
+pub struct GenesisDelegationRow {
+    pub delegator: String,
+    pub validator: String,
+    pub shares: String,
+    pub denom: String,
+    pub amount: u128,
+}
+
+impl TryFrom<&Row<'_>> for GenesisDelegationRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(GenesisDelegationRow {
+            delegator: row.get(0)?,
+            validator: row.get(1)?,
+            shares: row.get(2)?,
+            denom: row.get(3)?,
+            amount: tables::get_amount(row, 4)?,
+        })
+    }
+}
+
+/// Module parameters, kept as their JSON
+#[derive(Debug)]
+pub struct GenesisParamRow {
+    pub module: String,
+    pub key: String,
+    pub value: String,
+}
+
+const INSERT: &str = "INSERT INTO genesis (chain_id, height, time) VALUES (?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &GenesisRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![row.chain_id, row.height, row.time])
+        .map(fp::as_unit)
+}
+
+const GET: &str = "SELECT chain_id, height, time FROM genesis";
+pub fn get<T>(conn: &mut T) -> Result<Option<GenesisRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(GET)?
+        .query_row([], |row| GenesisRow::try_from(row))
+        .optional()
+}
+
+const INSERT_ACCOUNT: &str = "INSERT INTO genesis_account (address, type, account_number, sequence, name) VALUES (?,?,?,?,?)";
+pub fn insert_account<T>(conn: &mut T, row: &GenesisAccountRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_ACCOUNT)?
+        .execute(params![
+            row.address,
+            row.kind,
+            row.account_number,
+            row.sequence,
+            row.name
+        ])
+        .map(fp::as_unit)
+}
+
+const INSERT_BALANCE: &str = "INSERT INTO genesis_balance (address, denom, amount) VALUES (?,?,?)";
+pub fn insert_balance<T>(conn: &mut T, row: &GenesisBalanceRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_BALANCE)?
+        .execute(params![row.address, row.denom, row.amount.to_string()])
+        .map(fp::as_unit)
+}
+
+const BALANCES: &str = "SELECT address, denom, amount FROM genesis_balance";
+pub fn balances<T>(conn: &mut T) -> Result<Vec<GenesisBalanceRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BALANCES)?
+        .query_map([], |row| GenesisBalanceRow::try_from(row))?
+        .collect()
+}
+
+const INSERT_VALIDATOR: &str = "INSERT INTO genesis_validator (operator_address, consensus_pubkey, consensus_address, moniker, status, jailed, tokens, delegator_shares, commission_rate) VALUES (?,?,?,?,?,?,?,?,?)";
+pub fn insert_validator<T>(conn: &mut T, row: &GenesisValidatorRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_VALIDATOR)?
+        .execute(params![
+            row.operator_address,
+            row.consensus_pubkey,
+            row.consensus_address,
+            row.moniker,
+            row.status,
+            row.jailed,
+            row.tokens.to_string(),
+            row.delegator_shares,
+            row.commission_rate
+        ])
+        .map(fp::as_unit)
+}
+
+const VALIDATOR: &str = "SELECT operator_address, consensus_pubkey, consensus_address, moniker, status, jailed, tokens, delegator_shares, commission_rate FROM genesis_validator WHERE operator_address = ?";
+pub fn validator<T>(conn: &mut T, address: &str) -> Result<Option<GenesisValidatorRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(VALIDATOR)?
+        .query_row(params![address], |row| GenesisValidatorRow::try_from(row))
+        .optional()
+}
+
+const INSERT_DELEGATION: &str = "INSERT INTO genesis_delegation (delegator, validator, shares, denom, amount) VALUES (?,?,?,?,?)";
+pub fn insert_delegation<T>(conn: &mut T, row: &GenesisDelegationRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_DELEGATION)?
+        .execute(params![
+            row.delegator,
+            row.validator,
+            row.shares,
+            row.denom,
+            row.amount.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+const DELEGATIONS: &str =
+    "SELECT delegator, validator, shares, denom, amount FROM genesis_delegation";
+pub fn delegations<T>(conn: &mut T) -> Result<Vec<GenesisDelegationRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELEGATIONS)?
+        .query_map([], |row| GenesisDelegationRow::try_from(row))?
+        .collect()
+}
+
+const DELEGATIONS_BY_DELEGATOR: &str = "SELECT delegator, validator, shares, denom, amount FROM genesis_delegation WHERE delegator = ?";
+pub fn delegations_by_delegator<T>(
+    conn: &mut T,
+    delegator: &str,
+) -> Result<Vec<GenesisDelegationRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELEGATIONS_BY_DELEGATOR)?
+        .query_map(params![delegator], |row| {
+            GenesisDelegationRow::try_from(row)
+        })?
+        .collect()
+}
+
+const INSERT_PARAM: &str = "INSERT INTO genesis_param (module, key, value) VALUES (?,?,?)";
+pub fn insert_param<T>(conn: &mut T, row: &GenesisParamRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT_PARAM)?
+        .execute(params![row.module, row.key, row.value])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,151 @@
+use crate::fp;
+use crate::model;
+use crate::tables;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+pub const OUTBOUND: &str = "out";
+pub const INBOUND: &str = "in";
+
+pub const SENT: &str = "sent";
+pub const RECEIVED: &str = "received";
+pub const ACKED: &str = "acked";
+pub const ACK_ERROR: &str = "ack_error";
+pub const TIMED_OUT: &str = "timed_out";
+
+#[derive(Debug)]
+pub struct IbcTransferRow {
+    pub direction: String,
+    pub source_port: String,
+    pub source_channel: String,
+    pub sequence: u64,
+    pub destination_port: Option<String>,
+    pub destination_channel: Option<String>,
+    pub sender: String,
+    pub receiver: String,
+    pub denom: String,
+    pub amount: u128,
+    pub status: String,
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub updated: u64,
+}
+
+impl TryFrom<&Row<'_>> for IbcTransferRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(IbcTransferRow {
+            direction: row.get(0)?,
+            source_port: row.get(1)?,
+            source_channel: row.get(2)?,
+            sequence: row.get(3)?,
+            destination_port: row.get(4)?,
+            destination_channel: row.get(5)?,
+            sender: row.get(6)?,
+            receiver: row.get(7)?,
+            denom: row.get(8)?,
+            amount: tables::get_amount(row, 9)?,
+            status: row.get(10)?,
+            block: row.get(11)?,
+            tx: row.get(12)?,
+            msg: row.get(13)?,
+            updated: row.get(14)?,
+        })
+    }
+}
+
+impl IbcTransferRow {
+    pub fn new(
+        msg: &MsgRow,
+        direction: &str,
+        status: &str,
+        sequence: u64,
+        transfer: &model::IbcTransfer,
+    ) -> Self {
+        IbcTransferRow {
+            direction: direction.to_string(),
+            source_port: transfer.source_port.clone(),
+            source_channel: transfer.source_channel.clone(),
+            sequence,
+            destination_port: transfer.destination_port.clone(),
+            destination_channel: transfer.destination_channel.clone(),
+            sender: transfer.sender.clone(),
+            receiver: transfer.receiver.clone(),
+            denom: transfer.denom.clone(),
+            amount: transfer.amount,
+            status: status.to_string(),
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            updated: msg.block,
+        }
+    }
+}
+
+// Packets can be relayed more than once, the first delivery wins
+const INSERT: &str = "INSERT OR IGNORE INTO ibc_transfer (direction, source_port, source_channel, sequence, destination_port, destination_channel, sender, receiver, denom, amount, status, block, tx, msg, updated) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &IbcTransferRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.direction,
+            row.source_port,
+            row.source_channel,
+            row.sequence,
+            row.destination_port,
+            row.destination_channel,
+            row.sender,
+            row.receiver,
+            row.denom,
+            row.amount.to_string(),
+            row.status,
+            row.block,
+            row.tx,
+            row.msg,
+            row.updated
+        ])
+        .map(fp::as_unit)
+}
+
+const SET_STATUS: &str = "UPDATE ibc_transfer SET status = ?, updated = ? WHERE direction = ? AND source_port = ? AND source_channel = ? AND sequence = ?";
+pub fn set_status<T>(
+    conn: &mut T,
+    direction: &str,
+    packet: &model::IbcPacket,
+    status: &str,
+    block: u64,
+) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(SET_STATUS)?
+        .execute(params![
+            status,
+            block,
+            direction,
+            packet.source_port,
+            packet.source_channel,
+            packet.sequence
+        ])
+        .map(fp::as_unit)
+}
+
+const DELETE_SINCE: &str = "DELETE FROM ibc_transfer WHERE block >= ?";
+// Statuses only move on from the one a transfer is inserted with
+const RESET_STATUS_SINCE: &str = "
+    UPDATE ibc_transfer SET status = CASE direction WHEN ?2 THEN ?3 ELSE ?4 END, updated = block
+    WHERE updated >= ?1";
+/// Removes the transfers of `block` onward, along with the later status of earlier transfers
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?.execute(params![block])?;
+    conn.prepare_cached(RESET_STATUS_SINCE)?
+        .execute(params![block, OUTBOUND, SENT, RECEIVED])
+        .map(fp::as_unit)
+}
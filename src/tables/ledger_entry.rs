@@ -0,0 +1,179 @@
+use crate::fp;
+use crate::model;
+use crate::tables;
+use rusqlite::*;
+
+pub const TRANSFER: &str = "transfer";
+pub const FEE: &str = "fee";
+pub const DELEGATE: &str = "delegate";
+pub const REDELEGATE: &str = "redelegate";
+pub const UNDELEGATE: &str = "undelegate";
+pub const REWARD: &str = "reward";
+pub const COMMISSION: &str = "commission";
+pub const IBC_SEND: &str = "ibc_send";
+pub const IBC_RECEIVE: &str = "ibc_receive";
+pub const IBC_REFUND: &str = "ibc_refund";
+pub const GOV_DEPOSIT: &str = "gov_deposit";
+pub const COMMUNITY_POOL: &str = "community_pool";
+/// Supply created or destroyed, with no counterparty
+pub const MINT: &str = "mint";
+pub const BURN: &str = "burn";
+
+/// One side of a movement of value. The sender is debited, the receiver credited,
+/// each with the other as counterparty. Block level movements have no tx nor msg.
+#[derive(Debug)]
+pub struct LedgerEntryRow {
+    pub block: u64,
+    pub idx: u32,
+    pub tx: Option<u32>,
+    pub msg: Option<u32>,
+    pub kind: String,
+    pub address: String,
+    pub counterparty: String,
+    pub denom: String,
+    pub debit: u128,
+    pub credit: u128,
+}
+
+impl TryFrom<&Row<'_>> for LedgerEntryRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(LedgerEntryRow {
+            block: row.get(0)?,
+            idx: row.get(1)?,
+            tx: row.get(2)?,
+            msg: row.get(3)?,
+            kind: row.get(4)?,
+            address: row.get(5)?,
+            counterparty: row.get(6)?,
+            denom: row.get(7)?,
+            debit: tables::get_amount(row, 8)?,
+            credit: tables::get_amount(row, 9)?,
+        })
+    }
+}
+
+impl LedgerEntryRow {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        block: u64,
+        idx: u32,
+        tx: Option<u32>,
+        msg: Option<u32>,
+        kind: &str,
+        address: &str,
+        counterparty: &str,
+        coin: &model::Coin,
+        debit: bool,
+    ) -> Self {
+        LedgerEntryRow {
+            block,
+            idx,
+            tx,
+            msg,
+            kind: kind.to_string(),
+            address: address.to_string(),
+            counterparty: counterparty.to_string(),
+            denom: coin.denom.clone(),
+            debit: if debit { coin.amount } else { 0 },
+            credit: if debit { 0 } else { coin.amount },
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO ledger_entry (block, idx, tx, msg, kind, address, counterparty, denom, debit, credit) VALUES (?,?,?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &LedgerEntryRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.idx,
+            row.tx,
+            row.msg,
+            row.kind,
+            row.address,
+            row.counterparty,
+            row.denom,
+            row.debit.to_string(),
+            row.credit.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+const PAGE_BY_ADDRESS: &str = "SELECT block, idx, tx, msg, kind, address, counterparty, denom, debit, credit FROM ledger_entry WHERE address = ? ORDER BY block, idx LIMIT ? OFFSET ?";
+pub fn page_by_address<T>(
+    conn: &mut T,
+    address: &str,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<LedgerEntryRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(PAGE_BY_ADDRESS)?
+        .query_map(params![address, limit, offset], |row| {
+            LedgerEntryRow::try_from(row)
+        })?
+        .collect()
+}
+
+const BY_ADDRESS_BETWEEN: &str = "SELECT block, idx, tx, msg, kind, address, counterparty, denom, debit, credit FROM ledger_entry WHERE address = ? AND block > ? AND block <= ? ORDER BY block, idx";
+/// Entries of the address after block `from` up to block `to` included
+pub fn by_address_between<T>(
+    conn: &mut T,
+    address: &str,
+    from: Option<u64>,
+    to: u64,
+) -> Result<Vec<LedgerEntryRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_ADDRESS_BETWEEN)?
+        .query_map(
+            params![address, from.map(|it| it as i64).unwrap_or(-1), to],
+            |row| LedgerEntryRow::try_from(row),
+        )?
+        .collect()
+}
+
+const BY_DENOM_BETWEEN: &str = "SELECT block, idx, tx, msg, kind, address, counterparty, denom, debit, credit FROM ledger_entry WHERE denom = ? AND block > ? AND block <= ? ORDER BY block, idx";
+/// Entries in the denom after block `from` up to block `to` included
+pub fn by_denom_between<T>(
+    conn: &mut T,
+    denom: &str,
+    from: Option<u64>,
+    to: u64,
+) -> Result<Vec<LedgerEntryRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_DENOM_BETWEEN)?
+        .query_map(
+            params![denom, from.map(|it| it as i64).unwrap_or(-1), to],
+            |row| LedgerEntryRow::try_from(row),
+        )?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM ledger_entry WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
+
+const SINCE: &str = "SELECT block, idx, tx, msg, kind, address, counterparty, denom, debit, credit FROM ledger_entry WHERE block >= ? ORDER BY block, idx";
+pub fn since<T>(conn: &mut T, block: u64) -> Result<Vec<LedgerEntryRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(SINCE)?
+        .query_map(params![block], |row| LedgerEntryRow::try_from(row))?
+        .collect()
+}
@@ -1,5 +1,42 @@
 pub mod address_msg;
+pub mod archive;
+pub mod authz_grant;
+pub mod balance;
 pub mod block;
+pub mod commit_signature;
+pub mod contract;
+pub mod contract_execution;
+pub mod contract_history;
+pub mod cursor;
+pub mod delegation_change;
+pub mod event;
+pub mod genesis;
+pub mod ibc_transfer;
+pub mod ledger_entry;
 pub mod msg;
+pub mod msg_transfer;
+pub mod proposal;
+pub mod proposal_deposit;
+pub mod proposal_vote;
+pub mod reward_withdrawal;
 pub mod schema;
 pub mod tx;
+pub mod tx_fee;
+pub mod tx_signer;
+pub mod validator;
+pub mod validator_history;
+pub mod validator_set;
+
+/// SQLite integers are 64 bits wide, amounts are stored as decimal text
+pub fn get_amount(row: &rusqlite::Row, idx: usize) -> rusqlite::Result<u128> {
+    row.get::<_, String>(idx)?.parse().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// Same as `get_amount` for signed deltas
+pub fn get_signed_amount(row: &rusqlite::Row, idx: usize) -> rusqlite::Result<i128> {
+    row.get::<_, String>(idx)?.parse().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
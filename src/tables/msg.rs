@@ -7,6 +7,8 @@ pub struct MsgRow {
     pub block: u64,
     pub tx: u32,
     pub idx: u32,
+    /// Index of the wrapping message (authz MsgExec) if any
+    pub parent: Option<u32>,
     pub tag: String,
     pub data: Vec<u8>,
 }
@@ -19,8 +21,9 @@ impl TryFrom<&Row<'_>> for MsgRow {
             block: row.get(0)?,
             tx: row.get(1)?,
             idx: row.get(2)?,
-            tag: row.get(3)?,
-            data: row.get(4)?,
+            parent: row.get(3)?,
+            tag: row.get(4)?,
+            data: row.get(5)?,
         })
     }
 }
@@ -31,29 +34,52 @@ impl MsgRow {
             block: block.height,
             tx: tx.index,
             idx: msg.index,
+            parent: msg.parent,
             tag: msg.tag.clone(),
             data: msg.data.clone(),
         }
     }
 }
 
-const INSERT: &str = "INSERT INTO msg (block, tx, idx, tag, data) VALUES (?,?,?,?,?)";
+const INSERT: &str = "INSERT INTO msg (block, tx, idx, parent, tag, data) VALUES (?,?,?,?,?,?)";
 pub fn insert<T>(conn: &mut T, row: &MsgRow) -> Result<()>
 where
     T: core::ops::Deref<Target = Connection>,
 {
     conn.prepare_cached(INSERT)?
-        .execute(params![row.block, row.tx, row.idx, row.tag, row.data])
+        .execute(params![
+            row.block, row.tx, row.idx, row.parent, row.tag, row.data
+        ])
         .map(fp::as_unit)
 }
 
-const BY_BLOCK: &str = "SELECT (block, tx, idx, tag, data) FROM msg WHERE block = ?";
+const BY_BLOCK: &str = "SELECT block, tx, idx, parent, tag, data FROM msg WHERE block = ?";
 pub fn by_block<T>(conn: &mut T, block: u64) -> Result<Vec<MsgRow>>
 where
     T: core::ops::Deref<Target = Connection>,
 {
     conn.prepare_cached(BY_BLOCK)?
         .query_map(params![block], |row| MsgRow::try_from(row))?
-        .into_iter()
         .collect()
 }
+
+const BY_TX: &str =
+    "SELECT block, tx, idx, parent, tag, data FROM msg WHERE block = ? AND tx = ? ORDER BY idx";
+pub fn by_tx<T>(conn: &mut T, block: u64, tx: u32) -> Result<Vec<MsgRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_TX)?
+        .query_map(params![block, tx], |row| MsgRow::try_from(row))?
+        .collect()
+}
+
+const DELETE_BY_BLOCK: &str = "DELETE FROM msg WHERE block = ?";
+pub fn delete_by_block<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_BY_BLOCK)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
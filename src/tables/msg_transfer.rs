@@ -0,0 +1,89 @@
+use crate::fp;
+use crate::model;
+use crate::tables;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct MsgTransferRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub idx: u32,
+    pub sender: String,
+    pub receiver: String,
+    pub denom: String,
+    pub value: u128,
+}
+
+impl TryFrom<&Row<'_>> for MsgTransferRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(MsgTransferRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            msg: row.get(2)?,
+            idx: row.get(3)?,
+            sender: row.get(4)?,
+            receiver: row.get(5)?,
+            denom: row.get(6)?,
+            value: tables::get_amount(row, 7)?,
+        })
+    }
+}
+
+impl MsgTransferRow {
+    pub fn new(msg: &MsgRow, transfer: &model::Transfer) -> Self {
+        MsgTransferRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            idx: transfer.index,
+            sender: transfer.sender.clone(),
+            receiver: transfer.receiver.clone(),
+            denom: transfer.denom.clone(),
+            value: transfer.amount,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO msg_transfer (block, tx, msg, idx, sender, receiver, denom, value) VALUES (?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &MsgTransferRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.idx,
+            row.sender,
+            row.receiver,
+            row.denom,
+            row.value.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_BLOCK: &str =
+    "SELECT block, tx, msg, idx, sender, receiver, denom, value FROM msg_transfer WHERE block = ?";
+pub fn by_block<T>(conn: &mut T, block: u64) -> Result<Vec<MsgTransferRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_BLOCK)?
+        .query_map(params![block], |row| MsgTransferRow::try_from(row))?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM msg_transfer WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
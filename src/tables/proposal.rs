@@ -0,0 +1,100 @@
+use crate::fp;
+use crate::model;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct ProposalRow {
+    pub id: u64,
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub version: String,
+    pub proposer: String,
+    pub content_type: String,
+    pub title: String,
+    pub summary: String,
+}
+
+impl TryFrom<&Row<'_>> for ProposalRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ProposalRow {
+            id: row.get(0)?,
+            block: row.get(1)?,
+            tx: row.get(2)?,
+            msg: row.get(3)?,
+            version: row.get(4)?,
+            proposer: row.get(5)?,
+            content_type: row.get(6)?,
+            title: row.get(7)?,
+            summary: row.get(8)?,
+        })
+    }
+}
+
+impl ProposalRow {
+    pub fn new(msg: &MsgRow, id: u64, proposal: &model::Proposal) -> Self {
+        ProposalRow {
+            id,
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            version: proposal.version.clone(),
+            proposer: proposal.proposer.clone(),
+            content_type: proposal.content_type.clone(),
+            title: proposal.title.clone(),
+            summary: proposal.summary.clone(),
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO proposal (id, block, tx, msg, version, proposer, content_type, title, summary) VALUES (?,?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &ProposalRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.id,
+            row.block,
+            row.tx,
+            row.msg,
+            row.version,
+            row.proposer,
+            row.content_type,
+            row.title,
+            row.summary
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_ID: &str = "SELECT id, block, tx, msg, version, proposer, content_type, title, summary FROM proposal WHERE id = ?";
+pub fn by_id<T>(conn: &mut T, id: u64) -> Result<ProposalRow>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_ID)?
+        .query_row(params![id], |row| ProposalRow::try_from(row))
+}
+
+const PAGE: &str = "SELECT id, block, tx, msg, version, proposer, content_type, title, summary FROM proposal ORDER BY id DESC LIMIT ? OFFSET ?";
+pub fn page<T>(conn: &mut T, limit: u32, offset: u32) -> Result<Vec<ProposalRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(PAGE)?
+        .query_map(params![limit, offset], |row| ProposalRow::try_from(row))?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM proposal WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,96 @@
+use crate::fp;
+use crate::model;
+use crate::tables;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct ProposalDepositRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub idx: u32,
+    pub proposal_id: u64,
+    pub depositor: String,
+    pub denom: String,
+    pub amount: u128,
+}
+
+impl TryFrom<&Row<'_>> for ProposalDepositRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ProposalDepositRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            msg: row.get(2)?,
+            idx: row.get(3)?,
+            proposal_id: row.get(4)?,
+            depositor: row.get(5)?,
+            denom: row.get(6)?,
+            amount: tables::get_amount(row, 7)?,
+        })
+    }
+}
+
+impl ProposalDepositRow {
+    pub fn new(
+        msg: &MsgRow,
+        idx: u32,
+        proposal_id: u64,
+        depositor: &str,
+        coin: &model::Coin,
+    ) -> Self {
+        ProposalDepositRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            idx,
+            proposal_id,
+            depositor: depositor.to_string(),
+            denom: coin.denom.clone(),
+            amount: coin.amount,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO proposal_deposit (block, tx, msg, idx, proposal_id, depositor, denom, amount) VALUES (?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &ProposalDepositRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.idx,
+            row.proposal_id,
+            row.depositor,
+            row.denom,
+            row.amount.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_PROPOSAL: &str = "SELECT block, tx, msg, idx, proposal_id, depositor, denom, amount FROM proposal_deposit WHERE proposal_id = ? ORDER BY block, tx, msg, idx";
+pub fn by_proposal<T>(conn: &mut T, proposal_id: u64) -> Result<Vec<ProposalDepositRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_PROPOSAL)?
+        .query_map(params![proposal_id], |row| {
+            ProposalDepositRow::try_from(row)
+        })?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM proposal_deposit WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,109 @@
+use crate::fp;
+use crate::model;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct ProposalVoteRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub idx: u32,
+    pub proposal_id: u64,
+    pub voter: String,
+    pub option: String,
+    pub weight: String,
+}
+
+impl ProposalVoteRow {
+    pub fn new(msg: &MsgRow, idx: u32, vote: &model::Vote, option: &model::VoteOption) -> Self {
+        ProposalVoteRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            idx,
+            proposal_id: vote.proposal_id,
+            voter: vote.voter.clone(),
+            option: option.option.clone(),
+            weight: option.weight.clone(),
+        }
+    }
+}
+
+/// Weighted number of voters having picked an option
+#[derive(Debug)]
+pub struct TallyRow {
+    pub option: String,
+    pub weight: f64,
+}
+
+impl TryFrom<&Row<'_>> for TallyRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(TallyRow {
+            option: row.get(0)?,
+            weight: row.get(1)?,
+        })
+    }
+}
+
+const INSERT: &str = "INSERT INTO proposal_vote (block, tx, msg, idx, proposal_id, voter, option, weight) VALUES (?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &ProposalVoteRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.idx,
+            row.proposal_id,
+            row.voter,
+            row.option,
+            row.weight
+        ])
+        .map(fp::as_unit)
+}
+
+// Voters can change their mind, only their latest vote counts
+const TALLY: &str = "
+    SELECT v.option, SUM(CAST(v.weight AS REAL))
+    FROM proposal_vote v
+    WHERE v.proposal_id = ?1
+    AND NOT EXISTS (
+        SELECT 1 FROM proposal_vote w
+        WHERE w.proposal_id = v.proposal_id
+        AND w.voter = v.voter
+        AND (w.block, w.tx, w.msg) > (v.block, v.tx, v.msg)
+    )
+    GROUP BY v.option
+    ORDER BY v.option";
+pub fn tally<T>(conn: &mut T, proposal_id: u64) -> Result<Vec<TallyRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(TALLY)?
+        .query_map(params![proposal_id], |row| TallyRow::try_from(row))?
+        .collect()
+}
+
+const VOTERS: &str = "SELECT COUNT(DISTINCT voter) FROM proposal_vote WHERE proposal_id = ?";
+pub fn voters<T>(conn: &mut T, proposal_id: u64) -> Result<u64>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(VOTERS)?
+        .query_row(params![proposal_id], |row| row.get(0))
+}
+
+const DELETE_SINCE: &str = "DELETE FROM proposal_vote WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,100 @@
+use crate::fp;
+use crate::model;
+use crate::tables;
+use crate::tables::msg::MsgRow;
+use chrono::{DateTime, Utc};
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct RewardWithdrawalRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub idx: u32,
+    pub kind: String,
+    pub address: String,
+    pub validator: String,
+    pub denom: String,
+    pub amount: u128,
+}
+
+impl TryFrom<&Row<'_>> for RewardWithdrawalRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(RewardWithdrawalRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            msg: row.get(2)?,
+            idx: row.get(3)?,
+            kind: row.get(4)?,
+            address: row.get(5)?,
+            validator: row.get(6)?,
+            denom: row.get(7)?,
+            amount: tables::get_amount(row, 8)?,
+        })
+    }
+}
+
+impl RewardWithdrawalRow {
+    pub fn new(msg: &MsgRow, idx: u32, withdrawal: &model::Withdrawal, coin: &model::Coin) -> Self {
+        RewardWithdrawalRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            idx,
+            kind: withdrawal.kind.as_str().to_string(),
+            address: withdrawal.address.clone(),
+            validator: withdrawal.validator.clone(),
+            denom: coin.denom.clone(),
+            amount: coin.amount,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO reward_withdrawal (block, tx, msg, idx, kind, address, validator, denom, amount) VALUES (?,?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &RewardWithdrawalRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.idx,
+            row.kind,
+            row.address,
+            row.validator,
+            row.denom,
+            row.amount.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_ADDRESS_AND_TIME: &str = "SELECT r.block, r.tx, r.msg, r.idx, r.kind, r.address, r.validator, r.denom, r.amount FROM reward_withdrawal r JOIN block b ON b.height = r.block WHERE r.address = ? AND b.time BETWEEN ? AND ? ORDER BY r.block, r.tx, r.msg, r.idx";
+pub fn by_address_and_time<T>(
+    conn: &mut T,
+    address: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<RewardWithdrawalRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_ADDRESS_AND_TIME)?
+        .query_map(params![address, from, to], |row| {
+            RewardWithdrawalRow::try_from(row)
+        })?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM reward_withdrawal WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -1,5 +1,5 @@
 use rusqlite::*;
-use std::path::PathBuf;
+use std::path::Path;
 
 use crate::args::Args;
 
@@ -7,22 +7,39 @@ pub const SCHEMA: &str = unsafe { std::str::from_utf8_unchecked(include_bytes!("
 
 pub const DB_NAME: &str = "data.db";
 
-pub fn conn(datadir: &PathBuf) -> Result<Connection> {
+pub const LOCK_NAME: &str = "lock.db";
+
+pub fn conn(datadir: &Path) -> Result<Connection> {
     let db = datadir.join(DB_NAME);
     Connection::open(db)
 }
 
+/// Takes the lock of the datadir, so that indexing and reindexing never write to the same
+/// database from two processes. SQLite holds it until the connection is dropped, or the
+/// process exits however it does, and fails right away when it is already taken.
+pub fn lock(datadir: &Path) -> Result<Connection> {
+    let conn = Connection::open(datadir.join(LOCK_NAME))?;
+    conn.busy_timeout(std::time::Duration::ZERO)?;
+    conn.execute_batch("BEGIN EXCLUSIVE")?;
+    Ok(conn)
+}
+
 pub fn init(args: &Args) -> Result<()> {
     std::fs::create_dir_all(&args.datadir).unwrap();
 
     let conn = conn(&args.datadir)?;
+    create(&conn)?;
 
+    log::info!("Initialized new database schema in data.db");
+    Ok(())
+}
+
+/// Creates every table on the connection
+pub fn create(conn: &Connection) -> Result<()> {
     let stmts = SCHEMA.split(";").map(str::trim).filter(|s| !s.is_empty());
 
     for sql in stmts {
         conn.execute(sql, [])?;
     }
-
-    log::info!("Initialized new database schema in data.db");
     Ok(())
 }
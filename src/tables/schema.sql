@@ -10,37 +10,412 @@ CREATE UNIQUE INDEX `idx_block_time` ON `block`(`time`);
 CREATE UNIQUE INDEX `idx_block_hash` ON `block`(`hash`);
 
 CREATE TABLE `tx` (
-    `block` INTEGER REFERENCES `block`(`height`),
-    `idx`   INTEGER,
-    `hash`  TEXT,
+    `block`          INTEGER REFERENCES `block`(`height`),
+    `idx`            INTEGER,
+    `hash`           TEXT,
+    `code`           INTEGER,
+    `codespace`      TEXT,
+    `gas_wanted`     INTEGER,
+    `gas_used`       INTEGER,
+    `log`            TEXT,
+    `gas_limit`      INTEGER,
+    `payer`          TEXT,
+    `granter`        TEXT,
+    `memo`           TEXT,
+    `timeout_height` INTEGER,
     PRIMARY KEY (`block`, `idx`)
 );
 
 CREATE UNIQUE INDEX `idx_tx_hash` ON `tx`(`hash`);
 
+CREATE TABLE `tx_fee` (
+    `block`  INTEGER REFERENCES `block`(`height`),
+    `tx`     INTEGER REFERENCES `tx`(`idx`),
+    `idx`    INTEGER,
+    `denom`  TEXT,
+    `amount` TEXT,
+    PRIMARY KEY (`block`, `tx`, `idx`)
+);
+
+CREATE TABLE `tx_signer` (
+    `block`           INTEGER REFERENCES `block`(`height`),
+    `tx`              INTEGER REFERENCES `tx`(`idx`),
+    `idx`             INTEGER,
+    `public_key_type` TEXT,
+    `public_key`      TEXT,
+    `sequence`        INTEGER,
+    PRIMARY KEY (`block`, `tx`, `idx`)
+);
+
+CREATE INDEX `idx_tx_signer_public_key` ON `tx_signer`(`public_key`);
+
 CREATE TABLE `msg` (
-    `block` INTEGER REFERENCES `block`(`height`),
-    `tx`    INTEGER REFERENCES `tx`(`idx`),
-    `idx`   INTEGER,
-    `tag`   TEXT,
-    `data`  BLOB,
+    `block`  INTEGER REFERENCES `block`(`height`),
+    `tx`     INTEGER REFERENCES `tx`(`idx`),
+    `idx`    INTEGER,
+    `parent` INTEGER,
+    `tag`    TEXT,
+    `data`   BLOB,
     PRIMARY KEY (`block`,`tx`, `idx`)
 );
 
 CREATE TABLE `msg_transfer` (
     `block`    INTEGER REFERENCES `block`(`height`),
     `tx`       INTEGER REFERENCES `tx`(`idx`),
+    `msg`      INTEGER REFERENCES `msg`(`idx`),
     `idx`      INTEGER,
     `sender`   TEXT,
     `receiver` TEXT,
-    `value`    INTEGER
-    PRIMARY KEY (`block`,`tx`, `idx`)
+    `denom`    TEXT,
+    `value`    TEXT,
+    PRIMARY KEY (`block`,`tx`, `msg`, `idx`)
 );
 
 CREATE TABLE `address_msg` (
     `address` TEXT,
+    `role`    TEXT,
     `block`   INTEGER REFERENCES `block`(`height`),
     `tx`      INTEGER REFERENCES `tx`(`idx`),
     `msg`     INTEGER REFERENCES `msg`(`idx`),
     `id`      ROWID
 );
+
+CREATE TABLE `cursor` (
+    `name`   TEXT,
+    `height` INTEGER,
+    PRIMARY KEY (`name`)
+);
+
+CREATE TABLE `event` (
+    `block` INTEGER REFERENCES `block`(`height`),
+    `phase` TEXT,
+    `tx`    INTEGER REFERENCES `tx`(`idx`),
+    `idx`   INTEGER,
+    `type`  TEXT,
+    PRIMARY KEY (`block`, `phase`, `tx`, `idx`)
+);
+
+CREATE INDEX `idx_event_type` ON `event`(`type`);
+
+CREATE TABLE `event_attribute` (
+    `block` INTEGER REFERENCES `block`(`height`),
+    `phase` TEXT,
+    `tx`    INTEGER REFERENCES `tx`(`idx`),
+    `event` INTEGER REFERENCES `event`(`idx`),
+    `idx`   INTEGER,
+    `key`   TEXT,
+    `value` TEXT,
+    PRIMARY KEY (`block`, `phase`, `tx`, `event`, `idx`)
+);
+
+CREATE TABLE `proposal` (
+    `id`           INTEGER,
+    `block`        INTEGER REFERENCES `block`(`height`),
+    `tx`           INTEGER REFERENCES `tx`(`idx`),
+    `msg`          INTEGER REFERENCES `msg`(`idx`),
+    `version`      TEXT,
+    `proposer`     TEXT,
+    `content_type` TEXT,
+    `title`        TEXT,
+    `summary`      TEXT,
+    PRIMARY KEY (`id`)
+);
+
+CREATE TABLE `proposal_deposit` (
+    `block`       INTEGER REFERENCES `block`(`height`),
+    `tx`          INTEGER REFERENCES `tx`(`idx`),
+    `msg`         INTEGER REFERENCES `msg`(`idx`),
+    `idx`         INTEGER,
+    `proposal_id` INTEGER REFERENCES `proposal`(`id`),
+    `depositor`   TEXT,
+    `denom`       TEXT,
+    `amount`      TEXT,
+    PRIMARY KEY (`block`, `tx`, `msg`, `idx`)
+);
+
+CREATE INDEX `idx_proposal_deposit_proposal` ON `proposal_deposit`(`proposal_id`);
+
+CREATE TABLE `proposal_vote` (
+    `block`       INTEGER REFERENCES `block`(`height`),
+    `tx`          INTEGER REFERENCES `tx`(`idx`),
+    `msg`         INTEGER REFERENCES `msg`(`idx`),
+    `idx`         INTEGER,
+    `proposal_id` INTEGER REFERENCES `proposal`(`id`),
+    `voter`       TEXT,
+    `option`      TEXT,
+    `weight`      TEXT,
+    PRIMARY KEY (`block`, `tx`, `msg`, `idx`)
+);
+
+CREATE INDEX `idx_proposal_vote_proposal` ON `proposal_vote`(`proposal_id`, `voter`);
+
+CREATE TABLE `ibc_transfer` (
+    `direction`           TEXT,
+    `source_port`         TEXT,
+    `source_channel`      TEXT,
+    `sequence`            INTEGER,
+    `destination_port`    TEXT,
+    `destination_channel` TEXT,
+    `sender`              TEXT,
+    `receiver`            TEXT,
+    `denom`               TEXT,
+    `amount`              TEXT,
+    `status`              TEXT,
+    `block`               INTEGER REFERENCES `block`(`height`),
+    `tx`                  INTEGER REFERENCES `tx`(`idx`),
+    `msg`                 INTEGER REFERENCES `msg`(`idx`),
+    `updated`             INTEGER REFERENCES `block`(`height`),
+    PRIMARY KEY (`direction`, `source_port`, `source_channel`, `sequence`)
+);
+
+CREATE INDEX `idx_ibc_transfer_sender` ON `ibc_transfer`(`sender`);
+CREATE INDEX `idx_ibc_transfer_receiver` ON `ibc_transfer`(`receiver`);
+
+CREATE TABLE `authz_grant` (
+    `block`              INTEGER REFERENCES `block`(`height`),
+    `tx`                 INTEGER REFERENCES `tx`(`idx`),
+    `msg`                INTEGER REFERENCES `msg`(`idx`),
+    `granter`            TEXT,
+    `grantee`            TEXT,
+    `msg_type_url`       TEXT,
+    `authorization_type` TEXT,
+    `expiration`         TEXT,
+    `revoked`            INTEGER REFERENCES `block`(`height`),
+    PRIMARY KEY (`block`, `tx`, `msg`)
+);
+
+CREATE INDEX `idx_authz_grant_granter` ON `authz_grant`(`granter`, `grantee`, `msg_type_url`);
+CREATE INDEX `idx_authz_grant_grantee` ON `authz_grant`(`grantee`);
+
+CREATE TABLE `contract` (
+    `address` TEXT,
+    `code_id` INTEGER,
+    `creator` TEXT,
+    `admin`   TEXT,
+    `label`   TEXT,
+    `block`   INTEGER REFERENCES `block`(`height`),
+    `tx`      INTEGER REFERENCES `tx`(`idx`),
+    `msg`     INTEGER REFERENCES `msg`(`idx`),
+    PRIMARY KEY (`address`)
+);
+
+CREATE INDEX `idx_contract_code_id` ON `contract`(`code_id`);
+
+CREATE TABLE `contract_history` (
+    `block`            INTEGER REFERENCES `block`(`height`),
+    `tx`               INTEGER REFERENCES `tx`(`idx`),
+    `msg`              INTEGER REFERENCES `msg`(`idx`),
+    `address`          TEXT REFERENCES `contract`(`address`),
+    `kind`             TEXT,
+    `code_id`          INTEGER,
+    `admin`            TEXT,
+    `previous_code_id` INTEGER,
+    `previous_admin`   TEXT,
+    PRIMARY KEY (`block`, `tx`, `msg`)
+);
+
+CREATE INDEX `idx_contract_history_address` ON `contract_history`(`address`, `block`);
+
+CREATE TABLE `contract_execution` (
+    `block`    INTEGER REFERENCES `block`(`height`),
+    `tx`       INTEGER REFERENCES `tx`(`idx`),
+    `msg`      INTEGER REFERENCES `msg`(`idx`),
+    `contract` TEXT REFERENCES `contract`(`address`),
+    `sender`   TEXT,
+    `payload`  TEXT,
+    PRIMARY KEY (`block`, `tx`, `msg`)
+);
+
+CREATE INDEX `idx_contract_execution_contract` ON `contract_execution`(`contract`, `block`);
+
+CREATE TABLE `validator` (
+    `operator_address`           TEXT,
+    `consensus_pubkey`           TEXT,
+    `consensus_address`          TEXT,
+    `moniker`                    TEXT,
+    `identity`                   TEXT,
+    `website`                    TEXT,
+    `security_contact`           TEXT,
+    `details`                    TEXT,
+    `commission_rate`            TEXT,
+    `commission_max_rate`        TEXT,
+    `commission_max_change_rate` TEXT,
+    `min_self_delegation`        TEXT,
+    `created`                    INTEGER REFERENCES `block`(`height`),
+    `updated`                    INTEGER REFERENCES `block`(`height`),
+    PRIMARY KEY (`operator_address`)
+);
+
+CREATE INDEX `idx_validator_consensus_address` ON `validator`(`consensus_address`);
+
+CREATE TABLE `validator_history` (
+    `block`               INTEGER REFERENCES `block`(`height`),
+    `tx`                  INTEGER REFERENCES `tx`(`idx`),
+    `msg`                 INTEGER REFERENCES `msg`(`idx`),
+    `operator_address`    TEXT REFERENCES `validator`(`operator_address`),
+    `kind`                TEXT,
+    `moniker`             TEXT,
+    `identity`            TEXT,
+    `website`             TEXT,
+    `security_contact`    TEXT,
+    `details`             TEXT,
+    `commission_rate`     TEXT,
+    `min_self_delegation` TEXT,
+    PRIMARY KEY (`block`, `tx`, `msg`)
+);
+
+CREATE INDEX `idx_validator_history_operator` ON `validator_history`(`operator_address`);
+
+CREATE TABLE `commit_signature` (
+    `block`             INTEGER REFERENCES `block`(`height`),
+    `idx`               INTEGER,
+    `validator_address` TEXT,
+    `flag`              TEXT,
+    `timestamp`         TEXT,
+    PRIMARY KEY (`block`, `idx`)
+);
+
+CREATE INDEX `idx_commit_signature_validator` ON `commit_signature`(`validator_address`, `block`);
+
+CREATE TABLE `validator_set` (
+    `block`             INTEGER REFERENCES `block`(`height`),
+    `idx`               INTEGER,
+    `address`           TEXT,
+    `voting_power`      INTEGER,
+    `proposer_priority` INTEGER,
+    PRIMARY KEY (`block`, `idx`)
+);
+
+CREATE INDEX `idx_validator_set_address` ON `validator_set`(`address`);
+
+CREATE TABLE `delegation_change` (
+    `block`     INTEGER REFERENCES `block`(`height`),
+    `tx`        INTEGER REFERENCES `tx`(`idx`),
+    `msg`       INTEGER REFERENCES `msg`(`idx`),
+    `idx`       INTEGER,
+    `delegator` TEXT,
+    `validator` TEXT,
+    `denom`     TEXT,
+    `amount`    TEXT,
+    PRIMARY KEY (`block`, `tx`, `msg`, `idx`)
+);
+
+CREATE INDEX `idx_delegation_change_delegator` ON `delegation_change`(`delegator`);
+CREATE INDEX `idx_delegation_change_validator` ON `delegation_change`(`validator`, `block`);
+
+CREATE TABLE `reward_withdrawal` (
+    `block`     INTEGER REFERENCES `block`(`height`),
+    `tx`        INTEGER REFERENCES `tx`(`idx`),
+    `msg`       INTEGER REFERENCES `msg`(`idx`),
+    `idx`       INTEGER,
+    `kind`      TEXT,
+    `address`   TEXT,
+    `validator` TEXT,
+    `denom`     TEXT,
+    `amount`    TEXT,
+    PRIMARY KEY (`block`, `tx`, `msg`, `idx`)
+);
+
+CREATE INDEX `idx_reward_withdrawal_address` ON `reward_withdrawal`(`address`, `block`);
+
+CREATE TABLE `ledger_entry` (
+    `block`        INTEGER REFERENCES `block`(`height`),
+    `idx`          INTEGER,
+    `tx`           INTEGER REFERENCES `tx`(`idx`),
+    `msg`          INTEGER REFERENCES `msg`(`idx`),
+    `kind`         TEXT,
+    `address`      TEXT,
+    `counterparty` TEXT,
+    `denom`        TEXT,
+    `debit`        TEXT,
+    `credit`       TEXT,
+    PRIMARY KEY (`block`, `idx`)
+);
+
+CREATE INDEX `idx_ledger_entry_address` ON `ledger_entry`(`address`, `block`, `idx`);
+
+CREATE TABLE `balance` (
+    `block`   INTEGER,
+    `address` TEXT,
+    `denom`   TEXT,
+    `amount`  TEXT,
+    PRIMARY KEY (`address`, `denom`)
+);
+
+CREATE INDEX `idx_balance_block` ON `balance`(`block`);
+
+CREATE TABLE `balance_checkpoint` (
+    `block`   INTEGER,
+    `address` TEXT,
+    `denom`   TEXT,
+    `amount`  TEXT,
+    PRIMARY KEY (`address`, `denom`, `block`)
+);
+
+CREATE INDEX `idx_balance_checkpoint_denom` ON `balance_checkpoint`(`denom`, `block`);
+CREATE INDEX `idx_balance_checkpoint_block` ON `balance_checkpoint`(`block`);
+
+CREATE TABLE `genesis` (
+    `chain_id` TEXT,
+    `height`   INTEGER,
+    `time`     TEXT,
+    PRIMARY KEY (`chain_id`)
+);
+
+CREATE TABLE `genesis_account` (
+    `address`        TEXT,
+    `type`           TEXT,
+    `account_number` TEXT,
+    `sequence`       TEXT,
+    `name`           TEXT,
+    PRIMARY KEY (`address`)
+);
+
+CREATE TABLE `genesis_balance` (
+    `address` TEXT,
+    `denom`   TEXT,
+    `amount`  TEXT,
+    PRIMARY KEY (`address`, `denom`)
+);
+
+CREATE TABLE `genesis_validator` (
+    `operator_address`  TEXT,
+    `consensus_pubkey`  TEXT,
+    `consensus_address` TEXT,
+    `moniker`           TEXT,
+    `status`            TEXT,
+    `jailed`            INTEGER,
+    `tokens`            TEXT,
+    `delegator_shares`  TEXT,
+    `commission_rate`   TEXT,
+    PRIMARY KEY (`operator_address`)
+);
+
+CREATE TABLE `genesis_delegation` (
+    `delegator` TEXT,
+    `validator` TEXT,
+    `shares`    TEXT,
+    `denom`     TEXT,
+    `amount`    TEXT,
+    PRIMARY KEY (`delegator`, `validator`)
+);
+
+CREATE TABLE `genesis_param` (
+    `module` TEXT,
+    `key`    TEXT,
+    `value`  TEXT,
+    PRIMARY KEY (`module`, `key`)
+);
+
+CREATE TABLE `archive_tx` (
+    `block` INTEGER REFERENCES `block`(`height`),
+    `idx`   INTEGER,
+    `data`  BLOB,
+    PRIMARY KEY (`block`, `idx`)
+);
+
+CREATE TABLE `archive_block_results` (
+    `block` INTEGER REFERENCES `block`(`height`),
+    `data`  BLOB,
+    PRIMARY KEY (`block`)
+);
@@ -7,6 +7,16 @@ pub struct TxRow {
     pub block: u64,
     pub idx: u32,
     pub hash: String,
+    pub code: u32,
+    pub codespace: String,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+    pub log: String,
+    pub gas_limit: u64,
+    pub payer: Option<String>,
+    pub granter: Option<String>,
+    pub memo: String,
+    pub timeout_height: u64,
 }
 
 impl TryFrom<&Row<'_>> for TxRow {
@@ -17,6 +27,16 @@ impl TryFrom<&Row<'_>> for TxRow {
             block: row.get(0)?,
             idx: row.get(1)?,
             hash: row.get(2)?,
+            code: row.get(3)?,
+            codespace: row.get(4)?,
+            gas_wanted: row.get(5)?,
+            gas_used: row.get(6)?,
+            log: row.get(7)?,
+            gas_limit: row.get(8)?,
+            payer: row.get(9)?,
+            granter: row.get(10)?,
+            memo: row.get(11)?,
+            timeout_height: row.get(12)?,
         })
     }
 }
@@ -27,21 +47,45 @@ impl TxRow {
             block: block.height,
             idx: tx.index,
             hash: tx.hash.clone(),
+            code: tx.code,
+            codespace: tx.codespace.clone(),
+            gas_wanted: tx.gas_wanted,
+            gas_used: tx.gas_used,
+            log: tx.log.clone(),
+            gas_limit: tx.gas_limit,
+            payer: tx.payer.clone(),
+            granter: tx.granter.clone(),
+            memo: tx.memo.clone(),
+            timeout_height: tx.timeout_height,
         }
     }
 }
 
-const INSERT: &str = "INSERT INTO tx (block, idx, hash) VALUES (?,?,?)";
+const INSERT: &str = "INSERT INTO tx (block, idx, hash, code, codespace, gas_wanted, gas_used, log, gas_limit, payer, granter, memo, timeout_height) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?)";
 pub fn insert<T>(conn: &mut T, row: &TxRow) -> Result<()>
 where
     T: core::ops::Deref<Target = Connection>,
 {
     conn.prepare_cached(INSERT)?
-        .execute(params![row.block, row.idx, row.hash])
+        .execute(params![
+            row.block,
+            row.idx,
+            row.hash,
+            row.code,
+            row.codespace,
+            row.gas_wanted,
+            row.gas_used,
+            row.log,
+            row.gas_limit,
+            row.payer,
+            row.granter,
+            row.memo,
+            row.timeout_height
+        ])
         .map(fp::as_unit)
 }
 
-const BY_HASH: &str = "SELECT block, idx, hash FROM tx WHERE hash = ?";
+const BY_HASH: &str = "SELECT block, idx, hash, code, codespace, gas_wanted, gas_used, log, gas_limit, payer, granter, memo, timeout_height FROM tx WHERE hash = ?";
 pub fn by_hash<T>(conn: &mut T, hash: &String) -> Result<TxRow>
 where
     T: core::ops::Deref<Target = Connection>,
@@ -50,13 +94,22 @@ where
         .query_row(params![hash], |row| TxRow::try_from(row))
 }
 
-const BY_BLOCK: &str = "SELECT block, idx, hash FROM tx WHERE block = ?";
+const BY_BLOCK: &str = "SELECT block, idx, hash, code, codespace, gas_wanted, gas_used, log, gas_limit, payer, granter, memo, timeout_height FROM tx WHERE block = ?";
 pub fn by_block<T>(conn: &mut T, block: u64) -> Result<Vec<TxRow>>
 where
     T: core::ops::Deref<Target = Connection>,
 {
     conn.prepare_cached(BY_BLOCK)?
         .query_map(params![block], |row| TxRow::try_from(row))?
-        .into_iter()
         .collect()
 }
+
+const DELETE_BY_BLOCK: &str = "DELETE FROM tx WHERE block = ?";
+pub fn delete_by_block<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_BY_BLOCK)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,76 @@
+use crate::fp;
+use crate::model;
+use crate::tables;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct TxFeeRow {
+    pub block: u64,
+    pub tx: u32,
+    pub idx: u32,
+    pub denom: String,
+    pub amount: u128,
+}
+
+impl TryFrom<&Row<'_>> for TxFeeRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(TxFeeRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            idx: row.get(2)?,
+            denom: row.get(3)?,
+            amount: tables::get_amount(row, 4)?,
+        })
+    }
+}
+
+impl TxFeeRow {
+    pub fn new(block: &model::Block, tx: &model::Tx, idx: u32, coin: &model::Coin) -> Self {
+        TxFeeRow {
+            block: block.height,
+            tx: tx.index,
+            idx,
+            denom: coin.denom.clone(),
+            amount: coin.amount,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO tx_fee (block, tx, idx, denom, amount) VALUES (?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &TxFeeRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.idx,
+            row.denom,
+            row.amount.to_string()
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_TX: &str =
+    "SELECT block, tx, idx, denom, amount FROM tx_fee WHERE block = ? AND tx = ? ORDER BY idx";
+pub fn by_tx<T>(conn: &mut T, block: u64, tx: u32) -> Result<Vec<TxFeeRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_TX)?
+        .query_map(params![block, tx], |row| TxFeeRow::try_from(row))?
+        .collect()
+}
+
+const DELETE_BY_BLOCK: &str = "DELETE FROM tx_fee WHERE block = ?";
+pub fn delete_by_block<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_BY_BLOCK)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,78 @@
+use crate::fp;
+use crate::model;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct TxSignerRow {
+    pub block: u64,
+    pub tx: u32,
+    pub idx: u32,
+    pub public_key_type: Option<String>,
+    pub public_key: Option<String>,
+    pub sequence: u64,
+}
+
+impl TryFrom<&Row<'_>> for TxSignerRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(TxSignerRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            idx: row.get(2)?,
+            public_key_type: row.get(3)?,
+            public_key: row.get(4)?,
+            sequence: row.get(5)?,
+        })
+    }
+}
+
+impl TxSignerRow {
+    pub fn new(block: &model::Block, tx: &model::Tx, signer: &model::Signer) -> Self {
+        TxSignerRow {
+            block: block.height,
+            tx: tx.index,
+            idx: signer.index,
+            public_key_type: signer.public_key_type.clone(),
+            public_key: signer.public_key.clone(),
+            sequence: signer.sequence,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO tx_signer (block, tx, idx, public_key_type, public_key, sequence) VALUES (?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &TxSignerRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.idx,
+            row.public_key_type,
+            row.public_key,
+            row.sequence
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_TX: &str = "SELECT block, tx, idx, public_key_type, public_key, sequence FROM tx_signer WHERE block = ? AND tx = ? ORDER BY idx";
+pub fn by_tx<T>(conn: &mut T, block: u64, tx: u32) -> Result<Vec<TxSignerRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_TX)?
+        .query_map(params![block, tx], |row| TxSignerRow::try_from(row))?
+        .collect()
+}
+
+const DELETE_BY_BLOCK: &str = "DELETE FROM tx_signer WHERE block = ?";
+pub fn delete_by_block<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_BY_BLOCK)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
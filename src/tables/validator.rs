@@ -0,0 +1,170 @@
+use crate::fp;
+use crate::model;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct ValidatorRow {
+    pub operator_address: String,
+    pub consensus_pubkey: Option<String>,
+    pub consensus_address: Option<String>,
+    pub moniker: Option<String>,
+    pub identity: Option<String>,
+    pub website: Option<String>,
+    pub security_contact: Option<String>,
+    pub details: Option<String>,
+    pub commission_rate: Option<String>,
+    pub commission_max_rate: Option<String>,
+    pub commission_max_change_rate: Option<String>,
+    pub min_self_delegation: Option<String>,
+    /// Unknown for validators created before the first indexed block
+    pub created: Option<u64>,
+    pub updated: u64,
+}
+
+impl TryFrom<&Row<'_>> for ValidatorRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ValidatorRow {
+            operator_address: row.get(0)?,
+            consensus_pubkey: row.get(1)?,
+            consensus_address: row.get(2)?,
+            moniker: row.get(3)?,
+            identity: row.get(4)?,
+            website: row.get(5)?,
+            security_contact: row.get(6)?,
+            details: row.get(7)?,
+            commission_rate: row.get(8)?,
+            commission_max_rate: row.get(9)?,
+            commission_max_change_rate: row.get(10)?,
+            min_self_delegation: row.get(11)?,
+            created: row.get(12)?,
+            updated: row.get(13)?,
+        })
+    }
+}
+
+impl ValidatorRow {
+    pub fn new(block: u64, created: bool, validator: &model::Validator) -> Self {
+        ValidatorRow {
+            operator_address: validator.operator_address.clone(),
+            consensus_pubkey: validator.consensus_pubkey.clone(),
+            consensus_address: validator.consensus_address.clone(),
+            moniker: validator.moniker.clone(),
+            identity: validator.identity.clone(),
+            website: validator.website.clone(),
+            security_contact: validator.security_contact.clone(),
+            details: validator.details.clone(),
+            commission_rate: validator.commission_rate.clone(),
+            commission_max_rate: validator.commission_max_rate.clone(),
+            commission_max_change_rate: validator.commission_max_change_rate.clone(),
+            min_self_delegation: validator.min_self_delegation.clone(),
+            created: if created { Some(block) } else { None },
+            updated: block,
+        }
+    }
+}
+
+// Edits only carry the fields they change, anything left NULL keeps its current value
+const UPSERT: &str = "INSERT INTO validator (operator_address, consensus_pubkey, consensus_address, moniker, identity, website, security_contact, details, commission_rate, commission_max_rate, commission_max_change_rate, min_self_delegation, created, updated) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?)
+    ON CONFLICT (operator_address) DO UPDATE SET
+        consensus_pubkey = COALESCE(excluded.consensus_pubkey, consensus_pubkey),
+        consensus_address = COALESCE(excluded.consensus_address, consensus_address),
+        moniker = COALESCE(excluded.moniker, moniker),
+        identity = COALESCE(excluded.identity, identity),
+        website = COALESCE(excluded.website, website),
+        security_contact = COALESCE(excluded.security_contact, security_contact),
+        details = COALESCE(excluded.details, details),
+        commission_rate = COALESCE(excluded.commission_rate, commission_rate),
+        commission_max_rate = COALESCE(excluded.commission_max_rate, commission_max_rate),
+        commission_max_change_rate = COALESCE(excluded.commission_max_change_rate, commission_max_change_rate),
+        min_self_delegation = COALESCE(excluded.min_self_delegation, min_self_delegation),
+        created = COALESCE(created, excluded.created),
+        updated = excluded.updated";
+pub fn upsert<T>(conn: &mut T, row: &ValidatorRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(UPSERT)?
+        .execute(params![
+            row.operator_address,
+            row.consensus_pubkey,
+            row.consensus_address,
+            row.moniker,
+            row.identity,
+            row.website,
+            row.security_contact,
+            row.details,
+            row.commission_rate,
+            row.commission_max_rate,
+            row.commission_max_change_rate,
+            row.min_self_delegation,
+            row.created,
+            row.updated
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_OPERATOR_ADDRESS: &str = "SELECT operator_address, consensus_pubkey, consensus_address, moniker, identity, website, security_contact, details, commission_rate, commission_max_rate, commission_max_change_rate, min_self_delegation, created, updated FROM validator WHERE operator_address = ?";
+pub fn by_operator_address<T>(conn: &mut T, address: &str) -> Result<ValidatorRow>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_OPERATOR_ADDRESS)?
+        .query_row(params![address], |row| ValidatorRow::try_from(row))
+}
+
+const BY_CONSENSUS_ADDRESS: &str = "SELECT operator_address, consensus_pubkey, consensus_address, moniker, identity, website, security_contact, details, commission_rate, commission_max_rate, commission_max_change_rate, min_self_delegation, created, updated FROM validator WHERE consensus_address = ?";
+pub fn by_consensus_address<T>(conn: &mut T, address: &str) -> Result<ValidatorRow>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_CONSENSUS_ADDRESS)?
+        .query_row(params![address], |row| ValidatorRow::try_from(row))
+}
+
+const DELETE_CREATED_SINCE: &str = "DELETE FROM validator WHERE created >= ?";
+pub fn delete_created_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_CREATED_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
+
+// Rewinds put back every field, NULL included
+const REPLACE: &str = "INSERT OR REPLACE INTO validator (operator_address, consensus_pubkey, consensus_address, moniker, identity, website, security_contact, details, commission_rate, commission_max_rate, commission_max_change_rate, min_self_delegation, created, updated) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?)";
+pub fn replace<T>(conn: &mut T, row: &ValidatorRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(REPLACE)?
+        .execute(params![
+            row.operator_address,
+            row.consensus_pubkey,
+            row.consensus_address,
+            row.moniker,
+            row.identity,
+            row.website,
+            row.security_contact,
+            row.details,
+            row.commission_rate,
+            row.commission_max_rate,
+            row.commission_max_change_rate,
+            row.min_self_delegation,
+            row.created,
+            row.updated
+        ])
+        .map(fp::as_unit)
+}
+
+const DELETE: &str = "DELETE FROM validator WHERE operator_address = ?";
+pub fn delete<T>(conn: &mut T, address: &str) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE)?
+        .execute(params![address])
+        .map(fp::as_unit)
+}
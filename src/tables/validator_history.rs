@@ -0,0 +1,118 @@
+use crate::fp;
+use crate::model;
+use crate::tables::msg::MsgRow;
+use rusqlite::*;
+
+pub const CREATE: &str = "create";
+pub const EDIT: &str = "edit";
+
+/// One row per create or edit message, unchanged fields are NULL
+#[derive(Debug)]
+pub struct ValidatorHistoryRow {
+    pub block: u64,
+    pub tx: u32,
+    pub msg: u32,
+    pub operator_address: String,
+    pub kind: String,
+    pub moniker: Option<String>,
+    pub identity: Option<String>,
+    pub website: Option<String>,
+    pub security_contact: Option<String>,
+    pub details: Option<String>,
+    pub commission_rate: Option<String>,
+    pub min_self_delegation: Option<String>,
+}
+
+impl TryFrom<&Row<'_>> for ValidatorHistoryRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ValidatorHistoryRow {
+            block: row.get(0)?,
+            tx: row.get(1)?,
+            msg: row.get(2)?,
+            operator_address: row.get(3)?,
+            kind: row.get(4)?,
+            moniker: row.get(5)?,
+            identity: row.get(6)?,
+            website: row.get(7)?,
+            security_contact: row.get(8)?,
+            details: row.get(9)?,
+            commission_rate: row.get(10)?,
+            min_self_delegation: row.get(11)?,
+        })
+    }
+}
+
+impl ValidatorHistoryRow {
+    pub fn new(msg: &MsgRow, kind: &str, validator: &model::Validator) -> Self {
+        ValidatorHistoryRow {
+            block: msg.block,
+            tx: msg.tx,
+            msg: msg.idx,
+            operator_address: validator.operator_address.clone(),
+            kind: kind.to_string(),
+            moniker: validator.moniker.clone(),
+            identity: validator.identity.clone(),
+            website: validator.website.clone(),
+            security_contact: validator.security_contact.clone(),
+            details: validator.details.clone(),
+            commission_rate: validator.commission_rate.clone(),
+            min_self_delegation: validator.min_self_delegation.clone(),
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO validator_history (block, tx, msg, operator_address, kind, moniker, identity, website, security_contact, details, commission_rate, min_self_delegation) VALUES (?,?,?,?,?,?,?,?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &ValidatorHistoryRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.tx,
+            row.msg,
+            row.operator_address,
+            row.kind,
+            row.moniker,
+            row.identity,
+            row.website,
+            row.security_contact,
+            row.details,
+            row.commission_rate,
+            row.min_self_delegation
+        ])
+        .map(fp::as_unit)
+}
+
+const BY_OPERATOR_ADDRESS: &str = "SELECT block, tx, msg, operator_address, kind, moniker, identity, website, security_contact, details, commission_rate, min_self_delegation FROM validator_history WHERE operator_address = ? ORDER BY block, tx, msg";
+pub fn by_operator_address<T>(conn: &mut T, address: &str) -> Result<Vec<ValidatorHistoryRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(BY_OPERATOR_ADDRESS)?
+        .query_map(params![address], |row| ValidatorHistoryRow::try_from(row))?
+        .collect()
+}
+
+const OPERATORS_SINCE: &str =
+    "SELECT DISTINCT operator_address FROM validator_history WHERE block >= ?";
+pub fn operators_since<T>(conn: &mut T, block: u64) -> Result<Vec<String>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(OPERATORS_SINCE)?
+        .query_map(params![block], |row| row.get(0))?
+        .collect()
+}
+
+const DELETE_SINCE: &str = "DELETE FROM validator_history WHERE block >= ?";
+pub fn delete_since<T>(conn: &mut T, block: u64) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(DELETE_SINCE)?
+        .execute(params![block])
+        .map(fp::as_unit)
+}
@@ -0,0 +1,65 @@
+use crate::fp;
+use crate::model;
+use rusqlite::*;
+
+#[derive(Debug)]
+pub struct ValidatorSetRow {
+    pub block: u64,
+    pub idx: u32,
+    pub address: String,
+    pub voting_power: u64,
+    pub proposer_priority: i64,
+}
+
+impl TryFrom<&Row<'_>> for ValidatorSetRow {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(ValidatorSetRow {
+            block: row.get(0)?,
+            idx: row.get(1)?,
+            address: row.get(2)?,
+            voting_power: row.get(3)?,
+            proposer_priority: row.get(4)?,
+        })
+    }
+}
+
+impl ValidatorSetRow {
+    pub fn new(set: &model::ValidatorSet, validator: &model::ValidatorPower) -> Self {
+        ValidatorSetRow {
+            block: set.height,
+            idx: validator.index,
+            address: validator.address.clone(),
+            voting_power: validator.voting_power,
+            proposer_priority: validator.proposer_priority,
+        }
+    }
+}
+
+const INSERT: &str = "INSERT INTO validator_set (block, idx, address, voting_power, proposer_priority) VALUES (?,?,?,?,?)";
+pub fn insert<T>(conn: &mut T, row: &ValidatorSetRow) -> Result<()>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(INSERT)?
+        .execute(params![
+            row.block,
+            row.idx,
+            row.address,
+            row.voting_power,
+            row.proposer_priority
+        ])
+        .map(fp::as_unit)
+}
+
+// Most recent snapshot taken at or before the given height
+const LATEST: &str = "SELECT block, idx, address, voting_power, proposer_priority FROM validator_set WHERE block = (SELECT MAX(block) FROM validator_set WHERE block <= ?) ORDER BY idx";
+pub fn latest<T>(conn: &mut T, block: u64) -> Result<Vec<ValidatorSetRow>>
+where
+    T: core::ops::Deref<Target = Connection>,
+{
+    conn.prepare_cached(LATEST)?
+        .query_map(params![block], |row| ValidatorSetRow::try_from(row))?
+        .collect()
+}
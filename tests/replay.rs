@@ -0,0 +1,445 @@
+//! Runs the history indexer against the responses recorded in `tests/fixtures/replay`,
+//! served by the replay server as a stand-in for a node.
+//!
+//! The fixtures hold blocks 101 to 104 of a two validator chain :
+//! 101 is empty, 102 sends 1000uatom from alice to bob, 103 sends 250uatom back
+//! and holds a failed send, the second validator is absent from the commit of 102.
+//! 104 moves value to and from module accounts : a proposal with an initial deposit,
+//! a deposit, community pool funding, an instantiation with funds, rewards paid
+//! to the withdraw address of alice and a delegation of bob paying out the rewards of bob.
+//! Its begin block mints to the fee collector, then moves the collected fees to distribution.
+//! Only ledger tests go up to 104.
+
+use clap::Parser;
+use hyper::body::Buf;
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::time::{sleep, timeout, Duration, Instant};
+
+use quadrant::args::Args;
+use quadrant::decoder::Registry;
+use quadrant::{api, client, fetch, indexer, replay, tables};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/replay");
+
+const ALICE: &str = "cosmos190vqdjtlpcq27xslcveglfmr4ynfwg7gqmchsn";
+const BOB: &str = "cosmos1sxmr0k8u6trd5c6eu6trzyapzux7090y3u5dan";
+const CAROL: &str = "cosmos1fsndjp6vylvfahjeyuxq4s2tw8s8rv2jzx6033";
+const CONTRACT: &str = "cosmos1ejpjr43ht3y56pplm5pxpusmcrk9rkkvna4tklusnnwdxpqm0zls82k3wx";
+
+const FEE_COLLECTOR: &str = "cosmos17xpfvakm2amg962yls6f84z3kell8c5lserqta";
+const GOV: &str = "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn";
+const DISTRIBUTION: &str = "cosmos1jv65s3grqf6v6jl3dp4t6c9t9rk99cd88lyufl";
+const MINT: &str = "cosmos1m3h30wlvsf8llruxtpukdvsy0km2kum8g38c8q";
+const BONDED_POOL: &str = "cosmos1fl48vsnmsdzcv85q5d2q4z5ajdha8yu34mf0eh";
+
+const TX_102: &str = "5C085BF8BBD4D8C8C27F2CC569C363590D48746F16EB16C8AEC9ABD6F3067A7A";
+
+const SEND: &str = "/cosmos.bank.v1beta1.MsgSend";
+
+fn datadir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quadrant-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Indexes blocks 101 to 103 of the fixtures into a fresh database
+async fn index_fixtures(name: &str, extra: &[&str]) -> Args {
+    index_range(name, 103, extra).await
+}
+
+/// Indexes the fixtures from 101 up to `to` into a fresh database, through the replay server
+async fn index_range(name: &str, to: u64, extra: &[&str]) -> Args {
+    let any: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    let (addr, server) = replay::serve(Path::new(FIXTURES), &any).unwrap();
+    tokio::spawn(server);
+
+    let datadir = datadir(name);
+    let rpc = format!("http://{}", addr);
+    let to = to.to_string();
+    let mut argv = vec![
+        "quadrant",
+        "--datadir",
+        datadir.to_str().unwrap(),
+        "--rpc",
+        &rpc,
+        "--rpc-rate",
+        "0",
+        "--from-block",
+        "101",
+        "--to-block",
+        &to,
+    ];
+    argv.extend(extra);
+    let args = Args::parse_from(argv);
+    tables::schema::init(&args).unwrap();
+
+    let history = {
+        let args = args.clone();
+        let registry = Arc::new(Registry::default());
+        tokio::spawn(async move { indexer::index_history(&args, registry).await })
+    };
+
+    wait_for(&args, "SELECT MAX(height) FROM block", args.to_block as u64).await;
+    history.abort();
+
+    args
+}
+
+/// Waits until `sql` returns `height`, as indexers get there
+async fn wait_for(args: &Args, sql: &str, height: u64) {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while rows::<Option<u64>>(args, sql) != [[Some(height)]] {
+        assert!(
+            Instant::now() < deadline,
+            "fixtures were not indexed in time"
+        );
+        sleep(Duration::from_millis(50)).await;
+    }
+}
+
+fn rows<T: rusqlite::types::FromSql>(args: &Args, sql: &str) -> Vec<Vec<T>> {
+    let conn = tables::schema::conn(&args.datadir).unwrap();
+    let mut stmt = conn.prepare(sql).unwrap();
+    let columns = stmt.column_count();
+    stmt.query_map([], |row| (0..columns).map(|i| row.get(i)).collect())
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap()
+}
+
+async fn status(addr: &SocketAddr, path: &str) -> hyper::StatusCode {
+    let uri = format!("http://{}{}", addr, path).parse().unwrap();
+    hyper::Client::new().get(uri).await.unwrap().status()
+}
+
+async fn get(addr: &SocketAddr, path: &str) -> Value {
+    let uri = format!("http://{}{}", addr, path).parse().unwrap();
+    let res = hyper::Client::new().get(uri).await.unwrap();
+    assert!(res.status().is_success(), "GET {} : {}", path, res.status());
+    let body = hyper::body::aggregate(res).await.unwrap();
+    serde_json::from_reader(body.reader()).unwrap()
+}
+
+#[tokio::test]
+async fn indexes_recorded_blocks() {
+    let args = index_fixtures("blocks", &[]).await;
+
+    let blocks: Vec<Vec<u64>> = rows(&args, "SELECT height FROM block ORDER BY height");
+    assert_eq!(blocks, vec![vec![101], vec![102], vec![103]]);
+
+    let txs: Vec<Vec<String>> = rows(
+        &args,
+        "SELECT block || ':' || idx, CAST(code AS TEXT), memo FROM tx ORDER BY block, idx",
+    );
+    assert_eq!(
+        txs,
+        vec![
+            vec!["102:0".to_string(), "0".to_string(), "first".to_string()],
+            vec!["103:0".to_string(), "0".to_string(), String::new()],
+            vec!["103:1".to_string(), "5".to_string(), String::new()],
+        ]
+    );
+
+    let msgs: Vec<Vec<String>> = rows(
+        &args,
+        "SELECT block || ':' || tx || ':' || idx, tag FROM msg ORDER BY block, tx, idx",
+    );
+    let expected: Vec<Vec<String>> = ["102:0:0", "103:0:0", "103:1:0"]
+        .iter()
+        .map(|id| vec![id.to_string(), SEND.to_string()])
+        .collect();
+    assert_eq!(msgs, expected);
+
+    let addresses: Vec<Vec<String>> = rows(
+        &args,
+        "SELECT address, role, block || ':' || tx || ':' || msg FROM address_msg
+         ORDER BY block, tx, msg, role DESC",
+    );
+    let expected: Vec<Vec<String>> = [
+        (ALICE, "sender", "102:0:0"),
+        (BOB, "receiver", "102:0:0"),
+        (BOB, "sender", "103:0:0"),
+        (ALICE, "receiver", "103:0:0"),
+        (ALICE, "sender", "103:1:0"),
+        (BOB, "receiver", "103:1:0"),
+    ]
+    .iter()
+    .map(|(address, role, msg)| vec![address.to_string(), role.to_string(), msg.to_string()])
+    .collect();
+    assert_eq!(addresses, expected);
+
+    // The absent signature of the commit of 102 gets its address from the recorded validator set
+    let absent: Vec<Vec<Option<String>>> = rows(
+        &args,
+        "SELECT validator_address FROM commit_signature WHERE block = 102 AND idx = 1",
+    );
+    let signed: Vec<Vec<Option<String>>> = rows(
+        &args,
+        "SELECT validator_address FROM commit_signature WHERE block = 101 AND idx = 1",
+    );
+    assert!(absent[0][0].is_some());
+    assert_eq!(absent, signed);
+}
+
+#[tokio::test]
+async fn serves_indexed_blocks() {
+    let args = index_fixtures("api", &[]).await;
+
+    let any: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    let app = api::router(&args, Arc::new(Registry::default()));
+    let server = axum::Server::bind(&any).serve(app.into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let block = get(&addr, "/block/102").await;
+    assert_eq!(block["height"], 102);
+    assert_eq!(block["tx_count"], 1);
+    assert_eq!(block["tx_hashes"][0], TX_102);
+
+    let latest = get(&addr, "/block/latest").await;
+    assert_eq!(latest["height"], 103);
+    assert_eq!(latest["tx_count"], 2);
+
+    let msgs = get(&addr, &format!("/tx/{}/msgs", TX_102)).await;
+    assert_eq!(msgs[0]["tag"], SEND);
+    assert_eq!(msgs[0]["data"]["from_address"], ALICE);
+    assert_eq!(msgs[0]["data"]["to_address"], BOB);
+
+    let rewards = format!("/address/{}/rewards?from=yesterday", ALICE);
+    assert_eq!(
+        status(&addr, &rewards).await,
+        hyper::StatusCode::BAD_REQUEST
+    );
+
+    // An empty interval is rejected, one longer than the range is a single bucket
+    let delegations =
+        |interval: u64| format!("/validator/{}/delegations?interval={}", BOB, interval);
+    let bad_request = hyper::StatusCode::BAD_REQUEST;
+    assert_eq!(status(&addr, &delegations(0)).await, bad_request);
+    assert!(status(&addr, &delegations(u64::MAX)).await.is_success());
+}
+
+#[tokio::test]
+async fn archives_raw_blocks() {
+    let args = index_fixtures("archive", &["--archive"]).await;
+    let mut conn = tables::schema::conn(&args.datadir).unwrap();
+    let mut txn = conn.transaction().unwrap();
+
+    let recorded: Value = {
+        let path = Path::new(FIXTURES).join("block/103.json");
+        serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()
+    };
+    let archived = tables::archive::txs_by_block(&mut txn, 103).unwrap();
+    assert_eq!(archived.len(), 2);
+    for (row, tx) in archived.iter().zip(
+        recorded["result"]["block"]["data"]["txs"]
+            .as_array()
+            .unwrap(),
+    ) {
+        let raw = fetch::archived_tx(&row.data).unwrap();
+        assert_eq!(base64::encode(raw), tx.as_str().unwrap());
+    }
+
+    let row = tables::archive::block_results_by_block(&mut txn, 103)
+        .unwrap()
+        .unwrap();
+    let results = fetch::archived_block_results(&row.data).unwrap();
+    assert_eq!(results.height.value(), 103);
+    assert_eq!(results.txs_results.unwrap()[1].code.value(), 5);
+
+    assert!(tables::archive::txs_by_block(&mut txn, 101)
+        .unwrap()
+        .is_empty());
+    assert!(tables::archive::block_results_by_block(&mut txn, 101)
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+async fn reindexes_archived_blocks() {
+    let mut args = index_fixtures("reindex", &["--archive"]).await;
+    let dump = |args: &Args| {
+        let txs: Vec<Vec<String>> = rows(args, "SELECT hash, memo FROM tx ORDER BY block, idx");
+        let msgs: Vec<Vec<String>> = rows(
+            args,
+            "SELECT tag, hex(data) FROM msg ORDER BY block, tx, idx",
+        );
+        let addresses: Vec<Vec<String>> = rows(
+            args,
+            "SELECT address, role FROM address_msg ORDER BY block, tx, msg, role",
+        );
+        let events: Vec<Vec<String>> = rows(
+            args,
+            "SELECT phase, key, value FROM event_attribute ORDER BY block, phase, tx, event, idx",
+        );
+        (txs, msgs, addresses, events)
+    };
+    let indexed = dump(&args);
+
+    args.reindex_from = Some(102);
+    args.reindex_tables = ["tx", "msg", "event"].map(String::from).to_vec();
+    indexer::reindex::reindex(&args, &Registry::default()).unwrap();
+
+    assert_eq!(dump(&args), indexed);
+}
+
+#[tokio::test]
+async fn rebuilds_derived_tables_after_reindex() {
+    let mut args = index_fixtures("reindex-derived", &[]).await;
+    let transfers = |args: &Args| {
+        let args = args.clone();
+        let registry = Registry::default();
+        tokio::spawn(async move { indexer::transfers::index_transfers(&args, &registry).await })
+    };
+    let cursor = "SELECT height FROM cursor WHERE name = 'transfers'";
+    let dump = |args: &Args| -> Vec<Vec<String>> {
+        rows(
+            args,
+            "SELECT block || ':' || tx || ':' || msg || ':' || idx, sender, receiver, value
+             FROM msg_transfer ORDER BY block, tx, msg, idx",
+        )
+    };
+
+    let indexer = transfers(&args);
+    wait_for(&args, cursor, 103).await;
+    indexer.abort();
+    let indexed = dump(&args);
+    assert_eq!(indexed.len(), 2);
+
+    // The lock is what keeps a reindex away from an indexer running in another process
+    let lock = tables::schema::lock(&args.datadir).unwrap();
+    assert!(tables::schema::lock(&args.datadir).is_err());
+    drop(lock);
+
+    // Not archived, a repeated address_msg must not need the archive
+    args.reindex_from = Some(103);
+    args.reindex_tables = ["address_msg", "address_msg"].map(String::from).to_vec();
+    indexer::reindex::reindex(&args, &Registry::default()).unwrap();
+    wait_for(&args, cursor, 102).await;
+    assert_eq!(dump(&args), indexed[..1]);
+
+    let indexer = transfers(&args);
+    wait_for(&args, cursor, 103).await;
+    indexer.abort();
+    assert_eq!(dump(&args), indexed);
+}
+
+#[tokio::test]
+async fn reconstructs_balances() {
+    let args = index_range("ledger", 104, &[]).await;
+
+    let ledger = {
+        let args = args.clone();
+        let registry = Registry::default();
+        tokio::spawn(async move { indexer::ledger::index_ledger(&args, &registry).await })
+    };
+    let cursor = "SELECT height FROM cursor WHERE name = 'ledger'";
+    wait_for(&args, cursor, 104).await;
+    ledger.abort();
+
+    // Fees of 500, 300, 400 then 6 x 200, the deposits, funding and delegation are held by
+    // modules and the rewards are credited to the withdraw address rather than the delegator
+    let balances: Vec<Vec<String>> = rows(
+        &args,
+        "SELECT address, amount FROM balance WHERE denom = 'uatom' ORDER BY address",
+    );
+    let mut expected: Vec<Vec<String>> = [
+        (ALICE, -1000 - 500 + 250 - 400 - 600 - 100 - 30),
+        (BOB, 1000 - 250 - 300 - 600 - 50 - 70 + 15 - 300),
+        (BONDED_POOL, 300),
+        (CAROL, 40),
+        (CONTRACT, 30),
+        (DISTRIBUTION, 2200 + 70 - 40 - 15),
+        (FEE_COLLECTOR, 500 + 300 + 400 + 6 * 200 + 1000 - 2200),
+        (GOV, 100 + 50),
+        (MINT, 1000 - 1000),
+    ]
+    .iter()
+    .map(|(address, amount)| vec![address.to_string(), amount.to_string()])
+    .collect();
+    expected.sort();
+    assert_eq!(balances, expected);
+
+    // Messages and module events name what the bank events move
+    let entries: Vec<Vec<String>> = rows(
+        &args,
+        "SELECT kind, counterparty, address, credit FROM ledger_entry
+         WHERE block = 104 AND debit = '0' AND kind != 'fee' ORDER BY idx",
+    );
+    let expected: Vec<Vec<String>> = [
+        ("mint", "MULTI", MINT, 1000),
+        ("transfer", MINT, FEE_COLLECTOR, 1000),
+        ("transfer", FEE_COLLECTOR, DISTRIBUTION, 2200),
+        ("gov_deposit", ALICE, GOV, 100),
+        ("gov_deposit", BOB, GOV, 50),
+        ("community_pool", BOB, DISTRIBUTION, 70),
+        ("transfer", ALICE, CONTRACT, 30),
+        ("reward", DISTRIBUTION, CAROL, 40),
+        ("reward", DISTRIBUTION, BOB, 15),
+        ("delegate", BOB, BONDED_POOL, 300),
+    ]
+    .iter()
+    .map(|(kind, sender, receiver, amount)| {
+        let amount = amount.to_string();
+        [*kind, *sender, *receiver, &amount]
+            .map(str::to_string)
+            .to_vec()
+    })
+    .collect();
+    assert_eq!(entries, expected);
+
+    // Every movement has both sides, but for the minted supply
+    let total: Vec<Vec<i64>> = rows(
+        &args,
+        "SELECT SUM(CAST(credit AS INTEGER) - CAST(debit AS INTEGER)) FROM ledger_entry",
+    );
+    assert_eq!(total, [[1000]]);
+}
+
+#[tokio::test]
+async fn stops_on_undecodable_block() {
+    // Recorded blocks read from a copy where the tx of 102 is garbage
+    let source = datadir("undecodable-source");
+    for endpoint in ["block", "block_results", "validators"] {
+        std::fs::create_dir_all(source.join(endpoint)).unwrap();
+        for entry in std::fs::read_dir(Path::new(FIXTURES).join(endpoint)).unwrap() {
+            let path = entry.unwrap().path();
+            std::fs::copy(&path, source.join(endpoint).join(path.file_name().unwrap())).unwrap();
+        }
+    }
+    let path = source.join("block/102.json");
+    let mut block: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    block["result"]["block"]["data"]["txs"][0] = Value::from("AAAA");
+    std::fs::write(&path, block.to_string()).unwrap();
+
+    let datadir = datadir("undecodable");
+    let args = Args::parse_from([
+        "quadrant",
+        "--datadir",
+        datadir.to_str().unwrap(),
+        "--source-dir",
+        source.to_str().unwrap(),
+        "--from-block",
+        "101",
+        "--to-block",
+        "103",
+    ]);
+    tables::schema::init(&args).unwrap();
+
+    // Retrying would fail the same way, the error is returned rather than retried forever
+    let history = indexer::index_history(&args, Arc::new(Registry::default()));
+    let err = timeout(Duration::from_secs(30), history)
+        .await
+        .expect("history indexing kept retrying")
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        indexer::Error::Source(client::Error::Decode(_))
+    ));
+
+    let blocks: Vec<Vec<u64>> = rows(&args, "SELECT height FROM block ORDER BY height");
+    assert_eq!(blocks, vec![vec![101]]);
+}